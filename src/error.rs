@@ -1,53 +1,179 @@
 //! Ubiquitous types for error management.
 
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
 use std::io::Error as IoError;
 use std::num::ParseFloatError;
 use std::num::ParseIntError;
+use std::time::Duration;
 
 use quick_xml::Error as XmlError;
 use thiserror::Error;
 use ureq::Error as UreqError;
 
+use crate::CompoundProperty;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The context of an error returned by the Power User Gateway REST API.
+pub struct ErrorContext {
+    /// The message of the fault reported by the server.
+    pub message: String,
+    /// Additional details about the fault, if any.
+    pub details: Vec<String>,
+    /// The HTTP status code of the response, if any.
+    pub status: Option<u16>,
+    /// A description of the request that failed, if known.
+    ///
+    /// The description contains the path of the request after the API
+    /// base URL, followed by the input identifiers, truncated when needed.
+    pub request: Option<String>,
+    /// The delay to wait before retrying, if advised by the server.
+    pub retry_after: Option<Duration>,
+}
+
+impl Display for ErrorContext {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(&self.message)?;
+        if !self.details.is_empty() {
+            write!(f, " ({})", self.details.join("; "))?;
+        }
+        match (self.status, &self.request) {
+            (Some(status), Some(request)) => write!(f, " [HTTP {} for {}]", status, request),
+            (Some(status), None) => write!(f, " [HTTP {}]", status),
+            (None, Some(request)) => write!(f, " [for {}]", request),
+            (None, None) => Ok(()),
+        }?;
+        match self.retry_after {
+            Some(delay) => write!(f, " [retry after {}s]", delay.as_secs()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl From<String> for ErrorContext {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&str> for ErrorContext {
+    fn from(message: &str) -> Self {
+        Self::from(message.to_string())
+    }
+}
+
 #[derive(Debug, Error, Clone, PartialEq, Eq)]
 /// An error returned by the Power User Gateway REST API.
 pub enum ApiError {
     #[error("bad request: {0}")]
     /// Request is improperly formed.
-    BadRequest(String),
+    BadRequest(ErrorContext),
     #[error("not found: {0}")]
     /// The input record was not found.
-    NotFound(String),
+    NotFound(ErrorContext),
     #[error("not allowed: {0}")]
     /// Request not allowed.
-    NotAllowed(String),
+    NotAllowed(ErrorContext),
     #[error("timeout: {0}")]
     /// The request timed out, from server overload or too broad a request.
-    Timeout(String),
+    Timeout(ErrorContext),
     #[error("server busy: {0}")]
     /// Too many requests or server is busy, retry later.
-    ServerBusy(String),
+    ServerBusy(ErrorContext),
+    #[error("throttled: {0}")]
+    /// Too many requests were sent in a short time, retry later.
+    Throttled(ErrorContext),
     #[error("unimplemented!(): {0}")]
     /// The requested operation has not (yet) been implemented by the server.
-    Unimplemented(String),
+    Unimplemented(ErrorContext),
     #[error("server error: {0}")]
     /// Some problem on the server side (such as a database server down).
-    ServerError(String),
+    ServerError(ErrorContext),
     #[error("unknown error: {0}")]
     /// An unknown error occurred
-    Unknown(String),
+    Unknown(ErrorContext),
+}
+
+impl ApiError {
+    /// Get the context of the error.
+    pub fn context(&self) -> &ErrorContext {
+        match self {
+            ApiError::BadRequest(c)
+            | ApiError::NotFound(c)
+            | ApiError::NotAllowed(c)
+            | ApiError::Timeout(c)
+            | ApiError::ServerBusy(c)
+            | ApiError::Throttled(c)
+            | ApiError::Unimplemented(c)
+            | ApiError::ServerError(c)
+            | ApiError::Unknown(c) => c,
+        }
+    }
+
+    /// Get a mutable reference to the context of the error.
+    pub fn context_mut(&mut self) -> &mut ErrorContext {
+        match self {
+            ApiError::BadRequest(c)
+            | ApiError::NotFound(c)
+            | ApiError::NotAllowed(c)
+            | ApiError::Timeout(c)
+            | ApiError::ServerBusy(c)
+            | ApiError::Throttled(c)
+            | ApiError::Unimplemented(c)
+            | ApiError::ServerError(c)
+            | ApiError::Unknown(c) => c,
+        }
+    }
+
+    /// Create an error from the HTTP status code of a response.
+    ///
+    /// Used for error responses which do not contain a PubChem fault,
+    /// such as the error pages of an intermediate proxy.
+    pub fn from_status(status: u16, context: ErrorContext) -> Self {
+        match status {
+            400 => ApiError::BadRequest(context),
+            404 => ApiError::NotFound(context),
+            405 => ApiError::NotAllowed(context),
+            429 => ApiError::Throttled(context),
+            500 | 502 => ApiError::ServerError(context),
+            501 => ApiError::Unimplemented(context),
+            503 => ApiError::ServerBusy(context),
+            504 => ApiError::Timeout(context),
+            _ => ApiError::Unknown(context),
+        }
+    }
+
+    /// Get the message of the fault reported by the server.
+    pub fn message(&self) -> &str {
+        &self.context().message
+    }
 }
 
 impl From<crate::model::rest::Fault> for ApiError {
     fn from(fault: crate::model::rest::Fault) -> Self {
-        match fault.code.as_str() {
-            "PUGREST.BadRequest" => ApiError::BadRequest(fault.message),
-            "PUGREST.NotFound" => ApiError::NotFound(fault.message),
-            "PUGREST.NotAllowed" => ApiError::NotAllowed(fault.message),
-            "PUGREST.Timeout" => ApiError::Timeout(fault.message),
-            "PUGREST.ServerBusy" => ApiError::ServerBusy(fault.message),
-            "PUGREST.Unimplemented" => ApiError::Unimplemented(fault.message),
-            "PUGREST.ServerError" => ApiError::ServerError(fault.message),
-            _ => ApiError::Unknown(fault.message),
+        // PUG-REST and PUG-View faults share the same codes with a different prefix
+        let code = match fault.code.split_once('.') {
+            Some(("PUGREST" | "PUGVIEW", code)) => code,
+            _ => "",
+        };
+        let context = ErrorContext {
+            message: fault.message,
+            details: fault.details,
+            ..Default::default()
+        };
+        match code {
+            "BadRequest" => ApiError::BadRequest(context),
+            "NotFound" => ApiError::NotFound(context),
+            "NotAllowed" => ApiError::NotAllowed(context),
+            "Timeout" => ApiError::Timeout(context),
+            "ServerBusy" => ApiError::ServerBusy(context),
+            "Unimplemented" => ApiError::Unimplemented(context),
+            "ServerError" => ApiError::ServerError(context),
+            _ => ApiError::Unknown(context),
         }
     }
 }
@@ -61,6 +187,15 @@ pub enum ParseError {
     Int(#[from] ParseIntError),
     #[error(transparent)]
     Float(#[from] ParseFloatError),
+    #[error("invalid SDF record: {0}")]
+    /// An SDF record does not contain a well-formed MOL block.
+    Sdf(String),
+    #[error("invalid fingerprint: {0}")]
+    /// A PubChem fingerprint could not be decoded.
+    Fingerprint(String),
+    #[error("invalid date: {0}")]
+    /// A date is malformed or out of range.
+    DateTime(String),
 }
 
 // ---------------------------------------------------------------------------
@@ -75,7 +210,7 @@ pub enum Error {
     Api(#[from] ApiError),
     #[error(transparent)]
     /// The HTTP client encountered an error.
-    Request(#[from] UreqError),
+    Request(Box<UreqError>),
     #[error(transparent)]
     /// The XML parser encountered an error.
     ///
@@ -87,6 +222,26 @@ pub enum Error {
     #[error(transparent)]
     /// A parser returned an error.
     Parse(#[from] ParseError),
+    #[error("empty response")]
+    /// The PubChem API responded without any record.
+    EmptyResponse,
+    #[error("missing property: {}", .0.name())]
+    /// A requested compound property was not returned by the PubChem API.
+    MissingProperty(CompoundProperty),
+    #[error("unexpected element `{element}` in `{parent}`")]
+    /// The XML parser encountered an element it does not support.
+    UnexpectedElement {
+        /// The name of the unexpected element.
+        element: String,
+        /// The name of the element containing the unexpected element.
+        parent: String,
+    },
+}
+
+impl From<UreqError> for Error {
+    fn from(e: UreqError) -> Self {
+        Self::Request(Box::new(e))
+    }
 }
 
 impl From<IoError> for Error {
@@ -0,0 +1,478 @@
+//! Typed extraction of experimental properties from PUG-View records.
+//!
+//! Experimental properties are deposited in PubChem as free text (such as
+//! `135 °C` or `1.40 g/cu cm at 20 °C`), sometimes with a numeric value and
+//! a unit. This module extracts the numeric measurements from these values,
+//! normalizing temperatures so they can be compared across sources, while
+//! keeping the original text and the reference of each value.
+
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+use crate::model::view::Information;
+use crate::model::view::Record;
+use crate::model::view::Reference;
+
+/// The heading of the experimental properties section in PUG-View records.
+pub const HEADING: &str = "Experimental Properties";
+
+named_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    /// A common experimental property of a compound.
+    pub enum ExperimentalProperty {
+        /// The temperature at which the compound changes from solid to liquid.
+        MeltingPoint,
+        /// The temperature at which the vapor pressure of the compound equals the surrounding pressure.
+        BoilingPoint,
+        /// The lowest temperature at which the vapors of the compound ignite.
+        FlashPoint,
+        /// The lowest temperature at which the compound ignites spontaneously.
+        AutoignitionTemperature,
+        /// The mass per unit volume of the compound.
+        Density,
+        /// The ability of the compound to dissolve in a solvent.
+        Solubility,
+        /// The pressure of the vapor of the compound in equilibrium with its condensed phase.
+        VaporPressure,
+        /// The octanol-water partition coefficient of the compound.
+        LogP,
+        /// The refractive index of the compound.
+        RefractiveIndex,
+        /// The viscosity of the compound.
+        Viscosity,
+    }
+}
+
+impl ExperimentalProperty {
+    /// Get the heading of the PUG-View section for this property.
+    pub fn heading(&self) -> &'static str {
+        match self {
+            ExperimentalProperty::MeltingPoint => "Melting Point",
+            ExperimentalProperty::BoilingPoint => "Boiling Point",
+            ExperimentalProperty::FlashPoint => "Flash Point",
+            ExperimentalProperty::AutoignitionTemperature => "Autoignition Temperature",
+            ExperimentalProperty::Density => "Density",
+            ExperimentalProperty::Solubility => "Solubility",
+            ExperimentalProperty::VaporPressure => "Vapor Pressure",
+            ExperimentalProperty::LogP => "LogP",
+            ExperimentalProperty::RefractiveIndex => "Refractive Index",
+            ExperimentalProperty::Viscosity => "Viscosity",
+        }
+    }
+
+    /// Check whether the property is measured as a temperature.
+    pub fn is_temperature(&self) -> bool {
+        matches!(
+            self,
+            ExperimentalProperty::MeltingPoint
+                | ExperimentalProperty::BoilingPoint
+                | ExperimentalProperty::FlashPoint
+                | ExperimentalProperty::AutoignitionTemperature
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A unit of temperature.
+pub enum TemperatureUnit {
+    /// Degrees Celsius.
+    Celsius,
+    /// Degrees Fahrenheit.
+    Fahrenheit,
+    /// Kelvin.
+    Kelvin,
+}
+
+impl TemperatureUnit {
+    /// Parse a temperature unit at the start of the given text.
+    ///
+    /// Returns the unit and the remaining text on success.
+    fn parse_prefix(s: &str) -> Option<(Self, &str)> {
+        let s = s.trim_start();
+        let s = s
+            .strip_prefix('°')
+            .or_else(|| s.strip_prefix("deg"))
+            .or_else(|| s.strip_prefix("º"))
+            .map(str::trim_start)
+            .unwrap_or(s);
+        let unit = match s.chars().next()? {
+            'C' => TemperatureUnit::Celsius,
+            'F' => TemperatureUnit::Fahrenheit,
+            'K' => TemperatureUnit::Kelvin,
+            _ => return None,
+        };
+        // make sure the unit is not the start of a word (e.g. "Closed cup")
+        let rest = &s[1..];
+        match rest.chars().next() {
+            Some(c) if c.is_alphabetic() => None,
+            _ => Some((unit, rest)),
+        }
+    }
+}
+
+impl Display for TemperatureUnit {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            TemperatureUnit::Celsius => f.write_str("°C"),
+            TemperatureUnit::Fahrenheit => f.write_str("°F"),
+            TemperatureUnit::Kelvin => f.write_str("K"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// A temperature, in the unit it was reported with.
+pub struct Temperature {
+    /// The value of the temperature, in the original unit.
+    pub value: f64,
+    /// The original unit of the temperature.
+    pub unit: TemperatureUnit,
+}
+
+impl Temperature {
+    /// Create a new temperature with the given value and unit.
+    pub fn new(value: f64, unit: TemperatureUnit) -> Self {
+        Self { value, unit }
+    }
+
+    /// Get the temperature in Kelvin.
+    pub fn kelvin(&self) -> f64 {
+        match self.unit {
+            TemperatureUnit::Kelvin => self.value,
+            TemperatureUnit::Celsius => self.value + 273.15,
+            TemperatureUnit::Fahrenheit => (self.value - 32.0) * 5.0 / 9.0 + 273.15,
+        }
+    }
+
+    /// Get the temperature in degrees Celsius.
+    pub fn celsius(&self) -> f64 {
+        match self.unit {
+            TemperatureUnit::Celsius => self.value,
+            _ => self.kelvin() - 273.15,
+        }
+    }
+
+    /// Get the temperature in degrees Fahrenheit.
+    pub fn fahrenheit(&self) -> f64 {
+        match self.unit {
+            TemperatureUnit::Fahrenheit => self.value,
+            _ => self.celsius() * 9.0 / 5.0 + 32.0,
+        }
+    }
+}
+
+impl Display for Temperature {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{} {}", self.value, self.unit)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A measurement extracted from an experimental value.
+pub enum Measurement {
+    /// A temperature, or a range of temperatures.
+    Temperature {
+        /// The temperature, or the lower bound of the range.
+        low: Temperature,
+        /// The upper bound of the range, if the measurement is a range.
+        high: Option<Temperature>,
+    },
+    /// A quantity with an arbitrary unit, or a range of quantities.
+    Quantity {
+        /// The quantity, or the lower bound of the range.
+        low: f64,
+        /// The upper bound of the range, if the measurement is a range.
+        high: Option<f64>,
+        /// The unit of the quantity, if any.
+        unit: Option<String>,
+    },
+}
+
+impl Measurement {
+    /// Parse a measurement from free text.
+    ///
+    /// Temperatures are only recognized if `temperature` is `true`, in which
+    /// case a text without a temperature unit gives no measurement.
+    fn parse(text: &str, temperature: bool) -> Option<Self> {
+        let (low, rest) = parse_number(text)?;
+        let (high, rest) = parse_range_end(rest);
+        if temperature {
+            let (unit, _) = TemperatureUnit::parse_prefix(rest)?;
+            Some(Measurement::Temperature {
+                low: Temperature::new(low, unit),
+                high: high.map(|h| Temperature::new(h, unit)),
+            })
+        } else {
+            let unit = rest
+                .split(['(', ';', ',', '['])
+                .next()
+                .map(|u| u.split(" at ").next().unwrap_or(u).trim())
+                .filter(|u| !u.is_empty())
+                .map(String::from);
+            Some(Measurement::Quantity { low, high, unit })
+        }
+    }
+
+    /// Get the central value of the measurement, in degrees Celsius for temperatures.
+    pub fn value(&self) -> f64 {
+        match self {
+            Measurement::Temperature { low, high } => match high {
+                Some(high) => (low.celsius() + high.celsius()) / 2.0,
+                None => low.celsius(),
+            },
+            Measurement::Quantity { low, high, .. } => match high {
+                Some(high) => (low + high) / 2.0,
+                None => *low,
+            },
+        }
+    }
+}
+
+/// Parse the first decimal number in the given text.
+///
+/// Returns the number and the text following it.
+fn parse_number(s: &str) -> Option<(f64, &str)> {
+    let bytes = s.as_bytes();
+    let start = (0..bytes.len()).find(|&i| {
+        bytes[i].is_ascii_digit()
+            || (matches!(bytes[i], b'-' | b'+' | b'.')
+                && bytes.get(i + 1).is_some_and(u8::is_ascii_digit))
+    })?;
+    let mut end = start + 1;
+    let mut dot = bytes[start] == b'.';
+    while end < bytes.len() {
+        match bytes[end] {
+            b'0'..=b'9' => (),
+            b'.' if !dot && bytes.get(end + 1).is_some_and(u8::is_ascii_digit) => dot = true,
+            _ => break,
+        }
+        end += 1;
+    }
+    let number = s[start..end].parse().ok()?;
+    Some((number, &s[end..]))
+}
+
+/// Parse the upper bound of a range (`-140`, `to 140`) at the start of the text.
+fn parse_range_end(s: &str) -> (Option<f64>, &str) {
+    let trimmed = s.trim_start();
+    let after = trimmed
+        .strip_prefix('-')
+        .or_else(|| trimmed.strip_prefix('–'))
+        .or_else(|| trimmed.strip_prefix("to "));
+    match after {
+        Some(after) => {
+            let after = after.trim_start();
+            let starts_with_number = after
+                .bytes()
+                .next()
+                .is_some_and(|b| b.is_ascii_digit() || b == b'-');
+            match parse_number(after) {
+                Some((high, rest)) if starts_with_number => (Some(high), rest),
+                _ => (None, s),
+            }
+        }
+        None => (None, s),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// An experimental value of a property, as reported by a source.
+pub struct ExperimentalValue {
+    /// The property this value was reported for.
+    pub property: ExperimentalProperty,
+    /// The original text of the value.
+    pub text: String,
+    /// The measurement extracted from the value, if any.
+    pub measurement: Option<Measurement>,
+    /// The experimental conditions of the measurement, if given (e.g. `760 mm Hg`).
+    pub condition: Option<String>,
+    /// The reference to the source of the value, if available.
+    pub reference: Option<Reference>,
+    /// Bibliographic references given inline with the value.
+    pub citations: Vec<String>,
+}
+
+impl ExperimentalValue {
+    /// Extract an experimental value from a PUG-View information.
+    fn from_information(
+        property: ExperimentalProperty,
+        information: &Information,
+        record: &Record,
+    ) -> Option<Self> {
+        let value = &information.value;
+        let (text, measurement) = if let Some(&low) = value.number.first() {
+            let text = value.to_string();
+            let unit = value.unit.as_deref().unwrap_or_default();
+            let measurement = if property.is_temperature() {
+                TemperatureUnit::parse_prefix(unit).map(|(unit, _)| Measurement::Temperature {
+                    low: Temperature::new(low, unit),
+                    high: value.number.get(1).map(|&h| Temperature::new(h, unit)),
+                })
+            } else {
+                Some(Measurement::Quantity {
+                    low,
+                    high: value.number.get(1).cloned(),
+                    unit: value.unit.clone(),
+                })
+            };
+            (text, measurement)
+        } else {
+            let text = value.string_with_markup.first()?.string.clone();
+            let measurement = Measurement::parse(&text, property.is_temperature());
+            (text, measurement)
+        };
+
+        let condition = text
+            .split_once(" at ")
+            .map(|(_, c)| c.split(['(', ';', '[']).next().unwrap_or(c).trim())
+            .filter(|c| !c.is_empty())
+            .map(String::from);
+
+        Some(ExperimentalValue {
+            property,
+            text,
+            measurement,
+            condition,
+            reference: information
+                .reference_number
+                .and_then(|n| record.reference(n))
+                .cloned(),
+            citations: information.references.clone(),
+        })
+    }
+
+    /// Extract all the values of an experimental property from a PUG-View record.
+    pub fn extract(record: &Record, property: ExperimentalProperty) -> Vec<Self> {
+        record
+            .section(property.heading())
+            .map(|section| {
+                section
+                    .all_information()
+                    .filter_map(|i| Self::from_information(property, i, record))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::model::view::Section;
+    use crate::model::view::StringWithMarkup;
+    use crate::model::view::Value;
+
+    fn text(s: &str) -> Value {
+        Value {
+            string_with_markup: vec![StringWithMarkup {
+                string: s.to_string(),
+                markup: Vec::new(),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn temperature_conversions() {
+        let t = Temperature::new(135.0, TemperatureUnit::Celsius);
+        assert!((t.kelvin() - 408.15).abs() < 1e-9);
+        assert!((t.fahrenheit() - 275.0).abs() < 1e-9);
+        let t = Temperature::new(275.0, TemperatureUnit::Fahrenheit);
+        assert!((t.celsius() - 135.0).abs() < 1e-9);
+        let t = Temperature::new(408.15, TemperatureUnit::Kelvin);
+        assert!((t.celsius() - 135.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn measurement_parse_temperature() {
+        let m = Measurement::parse("135 °C", true).unwrap();
+        assert_eq!(
+            m,
+            Measurement::Temperature {
+                low: Temperature::new(135.0, TemperatureUnit::Celsius),
+                high: None
+            }
+        );
+        let m = Measurement::parse("-94.9 deg C", true).unwrap();
+        assert_eq!(m.value(), -94.9);
+        let m = Measurement::parse("138-140 °C", true).unwrap();
+        assert_eq!(m.value(), 139.0);
+        let m = Measurement::parse("MP: 275 °F (decomposes)", true).unwrap();
+        assert!((m.value() - 135.0).abs() < 1e-9);
+        let m = Measurement::parse("-10 to -5 °C", true).unwrap();
+        assert_eq!(m.value(), -7.5);
+        assert!(Measurement::parse("Decomposes before boiling", true).is_none());
+        assert!(Measurement::parse("250 Closed cup", true).is_none());
+    }
+
+    #[test]
+    fn measurement_parse_quantity() {
+        let m = Measurement::parse("1.40 g/cu cm at 20 °C", false).unwrap();
+        assert_eq!(
+            m,
+            Measurement::Quantity {
+                low: 1.4,
+                high: None,
+                unit: Some(String::from("g/cu cm"))
+            }
+        );
+        let m = Measurement::parse("4.6 mg/mL (25 °C)", false).unwrap();
+        assert_eq!(m.value(), 4.6);
+        assert!(Measurement::parse("Soluble in ethanol", false).is_none());
+    }
+
+    #[test]
+    fn extract_from_record() {
+        let record = Record {
+            record_number: 180,
+            sections: vec![Section {
+                toc_heading: String::from(HEADING),
+                sections: vec![Section {
+                    toc_heading: String::from("Boiling Point"),
+                    information: vec![
+                        Information {
+                            reference_number: Some(1),
+                            value: text("56.05 °C at 760 mm Hg"),
+                            references: vec![String::from("Haynes, W.M. (ed.) CRC Handbook")],
+                            ..Default::default()
+                        },
+                        Information {
+                            reference_number: Some(2),
+                            value: Value {
+                                number: vec![133.0],
+                                unit: Some(String::from("°F")),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                    ],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            references: vec![Reference {
+                reference_number: Some(1),
+                source_name: Some(String::from("HSDB")),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let values = ExperimentalValue::extract(&record, ExperimentalProperty::BoilingPoint);
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].condition.as_deref(), Some("760 mm Hg"));
+        assert_eq!(values[0].citations.len(), 1);
+        assert_eq!(
+            values[0].reference.as_ref().unwrap().source_name.as_deref(),
+            Some("HSDB")
+        );
+        assert!((values[0].measurement.as_ref().unwrap().value() - 56.05).abs() < 1e-9);
+        assert!((values[1].measurement.as_ref().unwrap().value() - 56.111).abs() < 1e-3);
+        assert!(values[1].reference.is_none());
+
+        let values = ExperimentalValue::extract(&record, ExperimentalProperty::MeltingPoint);
+        assert!(values.is_empty());
+    }
+}
@@ -0,0 +1,1102 @@
+//! Decoding and comparison of PubChem substructure fingerprints.
+//!
+//! The `Fingerprint2D` property of a compound is a base64-encoded binary
+//! fingerprint made of 881 substructure keys, as documented in the
+//! [PubChem Substructure Fingerprint](https://ftp.ncbi.nlm.nih.gov/pubchem/specifications/pubchem_fingerprints.txt)
+//! specification. The decoded data starts with a 4-byte big-endian integer
+//! giving the number of bits, followed by the bits themselves, packed with the
+//! most significant bit first.
+
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::iter::FromIterator;
+use std::ops::Range;
+use std::str::FromStr;
+use std::thread;
+
+use crate::error::ParseError;
+use crate::model::rest::Properties;
+use crate::utils::decode_base64;
+
+/// The number of substructure keys in a PubChem fingerprint.
+pub const FINGERPRINT_BITS: usize = 881;
+
+/// The number of bytes used to store the keys of a PubChem fingerprint.
+const FINGERPRINT_BYTES: usize = FINGERPRINT_BITS.div_ceil(8);
+
+/// The section of the PubChem specification a substructure key belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum KeySection {
+    /// Hierarchic element counts (bits 0 to 114).
+    ElementCounts,
+    /// Rings in a canonic Extended Smallest Set of Smallest Rings (bits 115 to 262).
+    Rings,
+    /// Simple atom pairs (bits 263 to 326).
+    AtomPairs,
+    /// Simple atom nearest neighbors (bits 327 to 415).
+    AtomNeighbors,
+    /// Detailed atom neighborhoods (bits 416 to 459).
+    DetailedNeighborhoods,
+    /// Simple SMARTS patterns (bits 460 to 712).
+    SimpleSmarts,
+    /// Complex SMARTS patterns (bits 713 to 880).
+    ComplexSmarts,
+}
+
+impl KeySection {
+    /// Get the section the given bit belongs to.
+    pub fn of(bit: usize) -> Option<Self> {
+        [
+            KeySection::ElementCounts,
+            KeySection::Rings,
+            KeySection::AtomPairs,
+            KeySection::AtomNeighbors,
+            KeySection::DetailedNeighborhoods,
+            KeySection::SimpleSmarts,
+            KeySection::ComplexSmarts,
+        ]
+        .iter()
+        .find(|section| section.bits().contains(&bit))
+        .copied()
+    }
+
+    /// Get the range of bits covered by the section.
+    pub fn bits(&self) -> Range<usize> {
+        match self {
+            KeySection::ElementCounts => 0..115,
+            KeySection::Rings => 115..263,
+            KeySection::AtomPairs => 263..327,
+            KeySection::AtomNeighbors => 327..416,
+            KeySection::DetailedNeighborhoods => 416..460,
+            KeySection::SimpleSmarts => 460..713,
+            KeySection::ComplexSmarts => 713..881,
+        }
+    }
+}
+
+/// The elements counted in section 1, with their count thresholds.
+const ELEMENT_COUNTS: &[(&str, &[u32])] = &[
+    ("H", &[4, 8, 16, 32]),
+    ("Li", &[1, 2]),
+    ("B", &[1, 2, 4]),
+    ("C", &[2, 4, 8, 16, 32]),
+    ("N", &[1, 2, 4, 8]),
+    ("O", &[1, 2, 4, 8, 16]),
+    ("F", &[1, 2, 4]),
+    ("Na", &[1, 2]),
+    ("Si", &[1, 2]),
+    ("P", &[1, 2, 4]),
+    ("S", &[1, 2, 4, 8]),
+    ("Cl", &[1, 2, 4, 8]),
+    ("K", &[1, 2]),
+    ("Br", &[1, 2, 4]),
+    ("I", &[1, 2, 4]),
+    ("Be", &[1]),
+    ("Mg", &[1]),
+    ("Al", &[1]),
+    ("Ca", &[1]),
+    ("Sc", &[1]),
+    ("Ti", &[1]),
+    ("V", &[1]),
+    ("Cr", &[1]),
+    ("Mn", &[1]),
+    ("Fe", &[1]),
+    ("Co", &[1]),
+    ("Ni", &[1]),
+    ("Cu", &[1]),
+    ("Zn", &[1]),
+    ("Ga", &[1]),
+    ("Ge", &[1]),
+    ("As", &[1]),
+    ("Se", &[1]),
+    ("Kr", &[1]),
+    ("Rb", &[1]),
+    ("Sr", &[1]),
+    ("Y", &[1]),
+    ("Zr", &[1]),
+    ("Nb", &[1]),
+    ("Mo", &[1]),
+    ("Ru", &[1]),
+    ("Rh", &[1]),
+    ("Pd", &[1]),
+    ("Ag", &[1]),
+    ("Cd", &[1]),
+    ("In", &[1]),
+    ("Sn", &[1]),
+    ("Sb", &[1]),
+    ("Te", &[1]),
+    ("Xe", &[1]),
+    ("Cs", &[1]),
+    ("Ba", &[1]),
+    ("Lu", &[1]),
+    ("Hf", &[1]),
+    ("Ta", &[1]),
+    ("W", &[1]),
+    ("Re", &[1]),
+    ("Os", &[1]),
+    ("Ir", &[1]),
+    ("Pt", &[1]),
+    ("Au", &[1]),
+    ("Hg", &[1]),
+    ("Tl", &[1]),
+    ("Pb", &[1]),
+    ("Bi", &[1]),
+    ("La", &[1]),
+    ("Ce", &[1]),
+    ("Pr", &[1]),
+    ("Nd", &[1]),
+    ("Pm", &[1]),
+    ("Sm", &[1]),
+    ("Eu", &[1]),
+    ("Gd", &[1]),
+    ("Tb", &[1]),
+    ("Dy", &[1]),
+    ("Ho", &[1]),
+    ("Er", &[1]),
+    ("Tm", &[1]),
+    ("Yb", &[1]),
+    ("Tc", &[1]),
+    ("U", &[1]),
+];
+
+/// The ring sizes counted in section 2, with their maximum count threshold.
+const RING_SIZES: &[(u32, u32)] = &[
+    (3, 2),
+    (4, 2),
+    (5, 5),
+    (6, 5),
+    (7, 2),
+    (8, 2),
+    (9, 1),
+    (10, 1),
+];
+
+/// The ring kinds counted for each ring size in section 2.
+const RING_KINDS: &[&str] = &[
+    "any ring",
+    "saturated or aromatic carbon-only ring",
+    "saturated or aromatic nitrogen-containing ring",
+    "saturated or aromatic heteroatom-containing ring",
+    "unsaturated non-aromatic carbon-only ring",
+    "unsaturated non-aromatic nitrogen-containing ring",
+    "unsaturated non-aromatic heteroatom-containing ring",
+];
+
+/// The atom pairs of section 3.
+const ATOM_PAIRS: &[&str] = &[
+    "Li-H", "Li-Li", "Li-B", "Li-C", "Li-O", "Li-F", "Li-P", "Li-S", "Li-Cl", "B-H", "B-B", "B-C",
+    "B-N", "B-O", "B-F", "B-Si", "B-P", "B-S", "B-Cl", "B-Br", "C-H", "C-C", "C-N", "C-O", "C-F",
+    "C-Na", "C-Mg", "C-Al", "C-Si", "C-P", "C-S", "C-Cl", "C-As", "C-Se", "C-Br", "C-I", "N-H",
+    "N-N", "N-O", "N-F", "N-Si", "N-P", "N-S", "N-Cl", "N-Br", "O-H", "O-O", "O-Mg", "O-Na",
+    "O-Al", "O-Si", "O-P", "O-K", "F-P", "F-S", "Al-H", "Al-Cl", "Si-H", "Si-Si", "Si-Cl", "P-H",
+    "P-P", "As-H", "As-As",
+];
+
+/// The simple atom neighbors of section 4, from bit 327 to bit 410.
+const ATOM_NEIGHBORS: &[&str] = &[
+    "C(~Br)(~C)",
+    "C(~Br)(~C)(~C)",
+    "C(~Br)(~H)",
+    "C(~Br)(:C)",
+    "C(~Br)(:N)",
+    "C(~C)(~C)",
+    "C(~C)(~C)(~C)",
+    "C(~C)(~C)(~C)(~C)",
+    "C(~C)(~C)(~C)(~H)",
+    "C(~C)(~C)(~C)(~N)",
+    "C(~C)(~C)(~C)(~O)",
+    "C(~C)(~C)(~H)(~N)",
+    "C(~C)(~C)(~H)(~O)",
+    "C(~C)(~C)(~N)",
+    "C(~C)(~C)(~O)",
+    "C(~C)(~Cl)",
+    "C(~C)(~Cl)(~H)",
+    "C(~C)(~H)",
+    "C(~C)(~H)(~N)",
+    "C(~C)(~H)(~O)",
+    "C(~C)(~H)(~O)(~O)",
+    "C(~C)(~H)(~P)",
+    "C(~C)(~H)(~S)",
+    "C(~C)(~I)",
+    "C(~C)(~N)",
+    "C(~C)(~O)",
+    "C(~C)(~S)",
+    "C(~C)(~Si)",
+    "C(~C)(:C)",
+    "C(~C)(:C)(:C)",
+    "C(~C)(:C)(:N)",
+    "C(~C)(:N)",
+    "C(~C)(:N)(:N)",
+    "C(~Cl)(~Cl)",
+    "C(~Cl)(~H)",
+    "C(~Cl)(:C)",
+    "C(~F)(~F)",
+    "C(~F)(:C)",
+    "C(~H)(~N)",
+    "C(~H)(~O)",
+    "C(~H)(~O)(~O)",
+    "C(~H)(~S)",
+    "C(~H)(~Si)",
+    "C(~H)(:C)",
+    "C(~H)(:C)(:C)",
+    "C(~H)(:C)(:N)",
+    "C(~H)(:N)",
+    "C(~H)(~H)(~H)",
+    "C(~N)(~N)",
+    "C(~N)(:C)",
+    "C(~N)(:N)",
+    "C(~O)(~O)",
+    "C(~O)(:C)",
+    "C(~O)(:N)",
+    "C(~S)(:C)",
+    "C(:C)(:C)",
+    "C(:C)(:N)",
+    "C(:N)(:N)",
+    "N(~C)(~C)",
+    "N(~C)(~C)(~C)",
+    "N(~C)(~C)(~H)",
+    "N(~C)(~H)",
+    "N(~C)(~H)(~N)",
+    "N(~C)(~O)",
+    "N(~C)(:C)",
+    "N(~C)(:C)(:C)",
+    "N(~H)(~N)",
+    "N(~H)(:C)",
+    "N(~H)(:C)(:C)",
+    "N(~O)(~O)",
+    "N(~O)(:O)",
+    "N(:C)(:C)",
+    "N(:C)(:C)(:C)",
+    "O(~C)(~C)",
+    "O(~C)(~H)",
+    "O(~C)(~P)",
+    "O(~H)(~S)",
+    "O(:C)(:C)",
+    "P(~C)(~C)",
+    "P(~O)(~O)",
+    "S(~C)(~C)",
+    "S(~C)(~H)",
+    "S(~C)(~O)",
+    "Si(~C)(~C)",
+];
+
+/// The detailed atom neighborhoods of section 5.
+const DETAILED_NEIGHBORHOODS: &[&str] = &[
+    "C=C",
+    "C#C",
+    "C=N",
+    "C#N",
+    "C=O",
+    "C=S",
+    "N=N",
+    "N=O",
+    "N=P",
+    "P=O",
+    "P=P",
+    "C(#C)(-C)",
+    "C(#C)(-H)",
+    "C(#N)(-C)",
+    "C(-C)(-C)(=C)",
+    "C(-C)(-C)(=N)",
+    "C(-C)(-C)(=O)",
+    "C(-C)(-Cl)(=O)",
+    "C(-C)(-H)(=C)",
+    "C(-C)(-H)(=N)",
+    "C(-C)(-H)(=O)",
+    "C(-C)(-N)(=C)",
+    "C(-C)(-N)(=N)",
+    "C(-C)(-N)(=O)",
+    "C(-C)(-O)(=O)",
+    "C(-C)(=C)",
+    "C(-C)(=N)",
+    "C(-C)(=O)",
+    "C(-Cl)(=O)",
+    "C(-H)(-N)(=C)",
+    "C(-H)(=C)",
+    "C(-H)(=N)",
+    "C(-H)(=O)",
+    "C(-N)(=C)",
+    "C(-N)(=N)",
+    "C(-N)(=O)",
+    "C(-O)(=O)",
+    "N(-C)(=C)",
+    "N(-C)(=O)",
+    "N(-O)(=O)",
+    "P(-O)(=O)",
+    "S(-C)(=O)",
+    "S(-O)(=O)",
+    "S(=O)(=O)",
+];
+
+/// The simple SMARTS patterns of section 6, from bit 460 to bit 573.
+const SIMPLE_SMARTS: &[&str] = &[
+    "C-C-C#C",
+    "O-C-C=N",
+    "O-C-C=O",
+    "N:C-S-[#1]",
+    "N-C-C=C",
+    "O=S-C-C",
+    "N#C-C=C",
+    "C=N-N-C",
+    "O=S-C-N",
+    "S-S-C:C",
+    "C:C-C=C",
+    "S:C:C:C",
+    "C:N:C-C",
+    "S-C:N:C",
+    "S:C:C:N",
+    "S-C=N-C",
+    "C-O-C=C",
+    "N-N-C:C",
+    "S-C=N-[#1]",
+    "S-C-S-C",
+    "C:S:C-C",
+    "O-S-C:C",
+    "C:N-C:C",
+    "N-S-C:C",
+    "N-C:N:C",
+    "N:C:C:N",
+    "N-C:N:N",
+    "N-C=N-C",
+    "N-C=N-[#1]",
+    "N-C-S-C",
+    "C-C-C=C",
+    "C-N:C-[#1]",
+    "N-C:O:C",
+    "O=C-C:C",
+    "O=C-C:N",
+    "C-N-C:C",
+    "N:N-C-[#1]",
+    "O-C:C:N",
+    "O-C=C-C",
+    "N-C:C:N",
+    "C-S-C:C",
+    "Cl-C:C-C",
+    "N-C=C-[#1]",
+    "Cl-C:C-[#1]",
+    "N:C:N-C",
+    "Cl-C:C-O",
+    "C-C:N:C",
+    "C-C-S-C",
+    "S=C-N-C",
+    "Br-C:C:C",
+    "[#1]-N-N-[#1]",
+    "S=C-N-[#1]",
+    "C-[As]-O-[#1]",
+    "S:C:C-[#1]",
+    "O-N-C-C",
+    "N-N-C-C",
+    "[#1]-C=C-[#1]",
+    "N-N-C-N",
+    "O=C-N-N",
+    "N=C-N-C",
+    "C=C-C:C",
+    "C:N-C-[#1]",
+    "C-N-N-[#1]",
+    "N:C:C-C",
+    "C-C=C-C",
+    "[As]-C:C-[#1]",
+    "Cl-C:C-Cl",
+    "C:C:N-[#1]",
+    "[#1]-N-C-[#1]",
+    "Cl-C-C-Cl",
+    "N:C-C:C",
+    "S-C:C-C",
+    "S-C:C-[#1]",
+    "S-C:C-N",
+    "S-C:C-O",
+    "O=C-C-C",
+    "O=C-C-N",
+    "O=C-C-O",
+    "N=C-C-C",
+    "N=C-C-[#1]",
+    "C-N-C-[#1]",
+    "O-C:C-C",
+    "O-C:C-[#1]",
+    "O-C:C-N",
+    "O-C:C-O",
+    "N-C:C-C",
+    "N-C:C-[#1]",
+    "N-C:C-N",
+    "O-C-C:C",
+    "N-C-C:C",
+    "Cl-C-C-C",
+    "Cl-C-C-O",
+    "C:C-C:C",
+    "O=C-C=C",
+    "Br-C-C-C",
+    "N=C-C=O",
+    "C=C-C-[#1]",
+    "N:C-O-[#1]",
+    "O=N-C:C",
+    "O-C-N-[#1]",
+    "N-C-N-C",
+    "Cl-C-C=O",
+    "Br-C-C=O",
+    "O-C-O-C",
+    "C=C-C=C",
+    "C:C-O-C",
+    "O-C-C-N",
+    "O-C-C-O",
+    "N#C-C-C",
+    "N-C-C-N",
+    "C:C-C-C",
+    "[#1]-C-O-[#1]",
+    "N:C:N:C",
+    "O-C-C=C",
+];
+
+/// The substituents paired on the ring templates of section 7.
+const SUBSTITUENTS: &[&str] = &["C", "O", "S", "N", "Cl", "Br"];
+
+/// The ring templates of section 7, with `X` and `Y` marking the substituents.
+const RING_TEMPLATES: &[&str] = &[
+    "Xc1ccc(Y)cc1",
+    "Xc1cc(Y)ccc1",
+    "Xc1c(Y)cccc1",
+    "XC1CCC(Y)CC1",
+    "XC1CC(Y)CCC1",
+    "XC1C(Y)CCCC1",
+    "XC1CC(Y)CC1",
+    "XC1C(Y)CCC1",
+];
+
+/// Get the name of the substructure key at the given bit.
+///
+/// Names follow the PubChem specification: element and ring counts are
+/// given as thresholds (such as `>= 4 H`), and other keys as SMARTS-like
+/// patterns. Returns `None` if the bit is out of range, or for the bits
+/// 411 to 415 and 574 to 712, whose names are not available yet.
+pub fn key_name(bit: usize) -> Option<String> {
+    let section = KeySection::of(bit)?;
+    let offset = bit - section.bits().start;
+    match section {
+        KeySection::ElementCounts => ELEMENT_COUNTS
+            .iter()
+            .flat_map(|(element, counts)| counts.iter().map(move |n| (element, n)))
+            .nth(offset)
+            .map(|(element, n)| format!(">= {} {}", n, element)),
+        KeySection::Rings => {
+            let mut names = RING_SIZES.iter().flat_map(|&(size, max)| {
+                (1..=max).flat_map(move |n| {
+                    RING_KINDS
+                        .iter()
+                        .map(move |kind| format!(">= {} {} size {}", n, kind, size))
+                })
+            });
+            match names.nth(offset) {
+                Some(name) => Some(name),
+                None => {
+                    let n = 1 + (offset - 140) / 2;
+                    let kind = ["aromatic", "hetero-aromatic"][(offset - 140) % 2];
+                    let plural = if n > 1 { "s" } else { "" };
+                    Some(format!(">= {} {} ring{}", n, kind, plural))
+                }
+            }
+        }
+        KeySection::AtomPairs => ATOM_PAIRS.get(offset).map(|s| s.to_string()),
+        KeySection::DetailedNeighborhoods => {
+            DETAILED_NEIGHBORHOODS.get(offset).map(|s| s.to_string())
+        }
+        KeySection::ComplexSmarts => {
+            let template = RING_TEMPLATES[offset / 21];
+            let (x, y) = SUBSTITUENTS
+                .iter()
+                .enumerate()
+                .flat_map(|(i, x)| SUBSTITUENTS[i..].iter().map(move |y| (x, y)))
+                .nth(offset % 21)?;
+            Some(template.replacen('X', x, 1).replacen('Y', y, 1))
+        }
+        KeySection::AtomNeighbors => ATOM_NEIGHBORS.get(offset).map(|s| s.to_string()),
+        KeySection::SimpleSmarts => SIMPLE_SMARTS.get(offset).map(|s| s.to_string()),
+    }
+}
+
+/// A decoded PubChem substructure fingerprint.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PubChemFingerprint {
+    bytes: [u8; FINGERPRINT_BYTES],
+}
+
+impl PubChemFingerprint {
+    /// Decode a fingerprint from its base64 encoding, as returned by PubChem.
+    pub fn from_base64(s: &str) -> Result<Self, ParseError> {
+        let decoded = decode_base64(s)
+            .ok_or_else(|| ParseError::Fingerprint(String::from("invalid base64 data")))?;
+        Self::from_bytes(&decoded)
+    }
+
+    /// Decode a fingerprint from its binary encoding, including the length prefix.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ParseError> {
+        if data.len() != 4 + FINGERPRINT_BYTES {
+            let msg = format!(
+                "expected {} bytes, got {}",
+                4 + FINGERPRINT_BYTES,
+                data.len()
+            );
+            return Err(ParseError::Fingerprint(msg));
+        }
+        let length = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+        if length != FINGERPRINT_BITS {
+            let msg = format!("expected {} bits, got {}", FINGERPRINT_BITS, length);
+            return Err(ParseError::Fingerprint(msg));
+        }
+        let mut bytes = [0; FINGERPRINT_BYTES];
+        bytes.copy_from_slice(&data[4..]);
+        // clear the padding bits so that they never contribute to a count
+        bytes[FINGERPRINT_BYTES - 1] &= 0xFF << (8 * FINGERPRINT_BYTES - FINGERPRINT_BITS);
+        Ok(Self { bytes })
+    }
+
+    /// Create a fingerprint with the given bits set.
+    ///
+    /// # Panics
+    /// If any of the bits is not lower than [`FINGERPRINT_BITS`].
+    pub fn from_bits<I: IntoIterator<Item = usize>>(bits: I) -> Self {
+        let mut bytes = [0; FINGERPRINT_BYTES];
+        for bit in bits {
+            assert!(bit < FINGERPRINT_BITS, "bit {} out of range", bit);
+            bytes[bit / 8] |= 0x80 >> (bit % 8);
+        }
+        Self { bytes }
+    }
+
+    /// Get the packed bits of the fingerprint, most significant bit first.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Check whether the substructure key at the given bit is set.
+    pub fn contains(&self, bit: usize) -> bool {
+        bit < FINGERPRINT_BITS && self.bytes[bit / 8] & (0x80 >> (bit % 8)) != 0
+    }
+
+    /// Get the number of substructure keys set in the fingerprint.
+    pub fn count_ones(&self) -> u32 {
+        self.bytes.iter().map(|b| b.count_ones()).sum()
+    }
+
+    /// Iterate over the indices of the substructure keys set in the fingerprint.
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..FINGERPRINT_BITS).filter(move |&bit| self.contains(bit))
+    }
+
+    /// Iterate over the names of the substructure keys set in the fingerprint.
+    ///
+    /// Keys without a known name are skipped, see [`key_name`].
+    pub fn names(&self) -> impl Iterator<Item = String> + '_ {
+        self.ones().filter_map(key_name)
+    }
+
+    /// Get the number of substructure keys set in both fingerprints.
+    pub fn intersection_count(&self, other: &Self) -> u32 {
+        self.bytes
+            .iter()
+            .zip(other.bytes.iter())
+            .map(|(a, b)| (a & b).count_ones())
+            .sum()
+    }
+
+    /// Compute the Tanimoto (Jaccard) similarity with another fingerprint.
+    ///
+    /// Two empty fingerprints have a similarity of zero.
+    pub fn tanimoto(&self, other: &Self) -> f64 {
+        let common = self.intersection_count(other);
+        let union = self.count_ones() + other.count_ones() - common;
+        if union == 0 {
+            0.0
+        } else {
+            common as f64 / union as f64
+        }
+    }
+
+    /// Compute the Dice (Sørensen) similarity with another fingerprint.
+    ///
+    /// Two empty fingerprints have a similarity of zero.
+    pub fn dice(&self, other: &Self) -> f64 {
+        let total = self.count_ones() + other.count_ones();
+        if total == 0 {
+            0.0
+        } else {
+            2.0 * self.intersection_count(other) as f64 / total as f64
+        }
+    }
+
+    /// Compute the cosine (Ochiai) similarity with another fingerprint.
+    ///
+    /// A fingerprint has a similarity of zero with an empty fingerprint.
+    pub fn cosine(&self, other: &Self) -> f64 {
+        let norm = (self.count_ones() as f64 * other.count_ones() as f64).sqrt();
+        if norm == 0.0 {
+            0.0
+        } else {
+            self.intersection_count(other) as f64 / norm
+        }
+    }
+}
+
+impl FromStr for PubChemFingerprint {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_base64(s)
+    }
+}
+
+impl Display for PubChemFingerprint {
+    /// Format the fingerprint as a string of `0` and `1`.
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        for bit in 0..FINGERPRINT_BITS {
+            f.write_str(if self.contains(bit) { "1" } else { "0" })?;
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+/// An upper bound of the Tanimoto similarity between two popcounts.
+fn tanimoto_bound(a: u32, b: u32) -> f64 {
+    let (min, max) = if a < b { (a, b) } else { (b, a) };
+    if max == 0 {
+        0.0
+    } else {
+        min as f64 / max as f64
+    }
+}
+
+/// A fingerprint stored in a [`FingerprintIndex`].
+#[derive(Debug, Clone)]
+struct Entry<K> {
+    key: K,
+    fingerprint: PubChemFingerprint,
+    count: u32,
+    index: usize,
+}
+
+/// A single result of a [`FingerprintIndex`] query.
+#[derive(Debug, PartialEq)]
+pub struct Hit<'a, K> {
+    /// The key of the matching fingerprint.
+    pub key: &'a K,
+    /// The Tanimoto similarity between the query and the matching fingerprint.
+    pub similarity: f64,
+    /// The insertion rank of the fingerprint, used to break ties.
+    index: usize,
+}
+
+impl<K> Hit<'_, K> {
+    /// Order hits by decreasing similarity, then by insertion order.
+    fn rank(&self, other: &Self) -> Ordering {
+        other
+            .similarity
+            .partial_cmp(&self.similarity)
+            .unwrap_or(Ordering::Equal)
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+/// An in-memory index of fingerprints for Tanimoto similarity searches.
+///
+/// Fingerprints are sorted by popcount, so that candidates which cannot
+/// reach the requested similarity are skipped without being compared, and
+/// the remaining candidates are compared in parallel.
+///
+/// # Example
+/// ```no_run
+/// use pubchem::fingerprint::FingerprintIndex;
+/// use pubchem::CompoundProperty;
+///
+/// let table = pubchem::Compounds::new(vec![2244, 3672, 1983, 2662])
+///     .properties(&[CompoundProperty::Fingerprint2D])
+///     .unwrap();
+/// let index = FingerprintIndex::from_properties(table.iter()).unwrap();
+///
+/// let query = table[0].fingerprint().unwrap().unwrap();
+/// for hit in index.top_k(&query, 3) {
+///     println!("CID {}: {:.3}", hit.key, hit.similarity);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FingerprintIndex<K> {
+    entries: Vec<Entry<K>>,
+    threads: usize,
+    inserted: usize,
+}
+
+impl<K> Default for FingerprintIndex<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> FingerprintIndex<K> {
+    /// Create a new empty index, using all available CPUs for queries.
+    pub fn new() -> Self {
+        let threads = thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        Self {
+            entries: Vec::new(),
+            threads,
+            inserted: 0,
+        }
+    }
+
+    /// Set the number of threads used to answer queries.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Add a fingerprint to the index under the given key.
+    pub fn insert(&mut self, key: K, fingerprint: PubChemFingerprint) {
+        let count = fingerprint.count_ones();
+        let position = self.entries.partition_point(|e| e.count <= count);
+        let index = self.inserted;
+        self.inserted += 1;
+        self.entries.insert(
+            position,
+            Entry {
+                key,
+                fingerprint,
+                count,
+                index,
+            },
+        );
+    }
+
+    /// Get the number of fingerprints in the index.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether the index contains no fingerprint.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<K: Sync> FingerprintIndex<K> {
+    /// Run `f` over chunks of `candidates` in parallel, and collect the results.
+    fn parallel<'a, T, F>(&self, candidates: &'a [Entry<K>], f: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(&'a [Entry<K>]) -> Vec<T> + Sync,
+    {
+        let threads = self.threads.min(candidates.len());
+        if threads <= 1 {
+            return f(candidates);
+        }
+        let size = candidates.len().div_ceil(threads);
+        thread::scope(|scope| {
+            let f = &f;
+            candidates
+                .chunks(size)
+                .map(|chunk| scope.spawn(move || f(chunk)))
+                .collect::<Vec<_>>()
+                .into_iter()
+                .flat_map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|e| std::panic::resume_unwind(e))
+                })
+                .collect()
+        })
+    }
+
+    /// Find all fingerprints with a Tanimoto similarity of at least `threshold`.
+    ///
+    /// Hits are sorted by decreasing similarity, then by insertion order.
+    pub fn threshold(&self, query: &PubChemFingerprint, threshold: f64) -> Vec<Hit<'_, K>> {
+        let count = query.count_ones();
+        let start = self
+            .entries
+            .partition_point(|e| e.count < count && tanimoto_bound(count, e.count) < threshold);
+        let end = self
+            .entries
+            .partition_point(|e| e.count <= count || tanimoto_bound(count, e.count) >= threshold);
+
+        let mut hits = self.parallel(&self.entries[start..end], |chunk| {
+            chunk
+                .iter()
+                .filter_map(|e| {
+                    let similarity = query.tanimoto(&e.fingerprint);
+                    if similarity >= threshold {
+                        Some(Hit {
+                            key: &e.key,
+                            similarity,
+                            index: e.index,
+                        })
+                    } else {
+                        None
+                    }
+                })
+                .collect()
+        });
+        hits.sort_by(Hit::rank);
+        hits
+    }
+
+    /// Find the `k` fingerprints most similar to the query.
+    ///
+    /// Hits are sorted by decreasing similarity, then by insertion order.
+    pub fn top_k(&self, query: &PubChemFingerprint, k: usize) -> Vec<Hit<'_, K>> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let count = query.count_ones();
+        let mut hits = self.parallel(&self.entries, |chunk| {
+            let mut best: Vec<Hit<K>> = Vec::with_capacity(k + 1);
+            for e in chunk {
+                if best.len() == k && tanimoto_bound(count, e.count) < best[k - 1].similarity {
+                    continue;
+                }
+                let hit = Hit {
+                    key: &e.key,
+                    similarity: query.tanimoto(&e.fingerprint),
+                    index: e.index,
+                };
+                let position = best.partition_point(|h| h.rank(&hit) == Ordering::Less);
+                if position < k {
+                    best.insert(position, hit);
+                    best.truncate(k);
+                }
+            }
+            best
+        });
+        hits.sort_by(Hit::rank);
+        hits.truncate(k);
+        hits
+    }
+}
+
+impl FingerprintIndex<i32> {
+    /// Build an index keyed by CID from compound properties.
+    ///
+    /// Compounds without a `Fingerprint2D` property are skipped.
+    pub fn from_properties<'a, I>(properties: I) -> Result<Self, ParseError>
+    where
+        I: IntoIterator<Item = &'a Properties>,
+    {
+        properties
+            .into_iter()
+            .filter_map(|p| p.fingerprint().map(|f| f.map(|f| (p.cid, f))))
+            .collect()
+    }
+}
+
+impl<K> Extend<(K, PubChemFingerprint)> for FingerprintIndex<K> {
+    fn extend<I: IntoIterator<Item = (K, PubChemFingerprint)>>(&mut self, iter: I) {
+        // push everything and sort once, a stable sort keeps entries with
+        // the same popcount in insertion order like `insert` does
+        for (key, fingerprint) in iter {
+            let count = fingerprint.count_ones();
+            let index = self.inserted;
+            self.inserted += 1;
+            self.entries.push(Entry {
+                key,
+                fingerprint,
+                count,
+                index,
+            });
+        }
+        self.entries.sort_by_key(|e| e.count);
+    }
+}
+
+impl<K> FromIterator<(K, PubChemFingerprint)> for FingerprintIndex<K> {
+    fn from_iter<I: IntoIterator<Item = (K, PubChemFingerprint)>>(iter: I) -> Self {
+        let mut index = Self::new();
+        index.extend(iter);
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn encode(fingerprint: &PubChemFingerprint) -> Vec<u8> {
+        let mut data = (FINGERPRINT_BITS as u32).to_be_bytes().to_vec();
+        data.extend_from_slice(fingerprint.as_bytes());
+        data
+    }
+
+    #[test]
+    fn sections() {
+        assert_eq!(KeySection::of(0), Some(KeySection::ElementCounts));
+        assert_eq!(KeySection::of(460), Some(KeySection::SimpleSmarts));
+        assert_eq!(KeySection::of(880), Some(KeySection::ComplexSmarts));
+        assert_eq!(KeySection::of(881), None);
+    }
+
+    #[test]
+    fn key_names() {
+        let elements = ELEMENT_COUNTS.iter().map(|(_, c)| c.len()).sum::<usize>();
+        assert_eq!(elements, KeySection::ElementCounts.bits().len());
+        assert_eq!(ATOM_PAIRS.len(), KeySection::AtomPairs.bits().len());
+        assert_eq!(
+            DETAILED_NEIGHBORHOODS.len(),
+            KeySection::DetailedNeighborhoods.bits().len()
+        );
+
+        assert_eq!(key_name(0).unwrap(), ">= 4 H");
+        assert_eq!(key_name(9).unwrap(), ">= 2 C");
+        assert_eq!(key_name(114).unwrap(), ">= 1 U");
+        assert_eq!(key_name(115).unwrap(), ">= 1 any ring size 3");
+        assert_eq!(key_name(143).unwrap(), ">= 1 any ring size 5");
+        assert_eq!(
+            key_name(254).unwrap(),
+            ">= 1 unsaturated non-aromatic heteroatom-containing ring size 10"
+        );
+        assert_eq!(key_name(255).unwrap(), ">= 1 aromatic ring");
+        assert_eq!(key_name(262).unwrap(), ">= 4 hetero-aromatic rings");
+        assert_eq!(key_name(263).unwrap(), "Li-H");
+        assert_eq!(key_name(326).unwrap(), "As-As");
+        assert_eq!(key_name(327).unwrap(), "C(~Br)(~C)");
+        assert_eq!(key_name(374).unwrap(), "C(~H)(~H)(~H)");
+        assert_eq!(key_name(410).unwrap(), "Si(~C)(~C)");
+        assert_eq!(key_name(416).unwrap(), "C=C");
+        assert_eq!(key_name(459).unwrap(), "S(=O)(=O)");
+        assert_eq!(key_name(460).unwrap(), "C-C-C#C");
+        assert_eq!(key_name(510).unwrap(), "[#1]-N-N-[#1]");
+        assert_eq!(key_name(573).unwrap(), "O-C-C=C");
+        assert_eq!(key_name(713).unwrap(), "Cc1ccc(C)cc1");
+        assert_eq!(key_name(733).unwrap(), "Brc1ccc(Br)cc1");
+        assert_eq!(key_name(734).unwrap(), "Cc1cc(C)ccc1");
+        assert_eq!(key_name(880).unwrap(), "BrC1C(Br)CCC1");
+        assert_eq!(key_name(881), None);
+
+        // section 5 starts with `C=C` at bit 416, after the 89 bits of section 4
+        assert_eq!(KeySection::of(415), Some(KeySection::AtomNeighbors));
+        assert_eq!(KeySection::of(416), Some(KeySection::DetailedNeighborhoods));
+        assert_eq!(ATOM_NEIGHBORS.len(), 411 - 327);
+        assert_eq!(SIMPLE_SMARTS.len(), 574 - 460);
+
+        for bit in 0..FINGERPRINT_BITS {
+            let unnamed = (411..416).contains(&bit) || (574..713).contains(&bit);
+            assert_eq!(key_name(bit).is_none(), unnamed, "bit {}", bit);
+        }
+    }
+
+    #[test]
+    fn decode() {
+        let fingerprint = PubChemFingerprint::from_bits(vec![0, 9, 255, 880]);
+        let decoded = PubChemFingerprint::from_bytes(&encode(&fingerprint)).unwrap();
+        assert_eq!(decoded, fingerprint);
+        assert_eq!(decoded.count_ones(), 4);
+        assert_eq!(decoded.ones().collect::<Vec<_>>(), vec![0, 9, 255, 880]);
+        assert!(decoded.contains(255));
+        assert!(!decoded.contains(256));
+        assert!(!decoded.contains(1000));
+
+        let mut padded = encode(&fingerprint);
+        padded[4 + FINGERPRINT_BYTES - 1] |= 0x7F;
+        assert_eq!(
+            PubChemFingerprint::from_bytes(&padded).unwrap(),
+            fingerprint
+        );
+    }
+
+    #[test]
+    fn decode_invalid() {
+        let mut data = encode(&PubChemFingerprint::from_bits(vec![]));
+        assert!(PubChemFingerprint::from_bytes(&data[..50]).is_err());
+        data[3] = 0;
+        assert!(PubChemFingerprint::from_bytes(&data).is_err());
+        assert!(PubChemFingerprint::from_base64("AAADcQ==").is_err());
+        assert!("not base64".parse::<PubChemFingerprint>().is_err());
+    }
+
+    #[test]
+    fn similarity() {
+        let a = PubChemFingerprint::from_bits(vec![1, 2, 3, 4]);
+        let b = PubChemFingerprint::from_bits(vec![3, 4, 5, 6]);
+        let empty = PubChemFingerprint::from_bits(vec![]);
+
+        assert_eq!(a.tanimoto(&a), 1.0);
+        assert_eq!(a.tanimoto(&b), 2.0 / 6.0);
+        assert_eq!(a.dice(&b), 0.5);
+        assert_eq!(a.cosine(&b), 0.5);
+        assert_eq!(a.tanimoto(&empty), 0.0);
+        assert_eq!(empty.tanimoto(&empty), 0.0);
+        assert_eq!(empty.cosine(&a), 0.0);
+    }
+
+    #[test]
+    fn index_threshold() {
+        let index = vec![
+            ("a", PubChemFingerprint::from_bits(vec![1, 2, 3, 4])),
+            ("b", PubChemFingerprint::from_bits(vec![1, 2, 3])),
+            ("c", PubChemFingerprint::from_bits(vec![5, 6, 7, 8])),
+            ("d", PubChemFingerprint::from_bits(vec![1])),
+            ("e", PubChemFingerprint::from_bits(vec![1, 2, 3, 4, 5, 6])),
+        ]
+        .into_iter()
+        .collect::<FingerprintIndex<_>>()
+        .threads(2);
+        assert_eq!(index.len(), 5);
+
+        let query = PubChemFingerprint::from_bits(vec![1, 2, 3, 4]);
+        let hits = index.threshold(&query, 0.6);
+        let keys = hits.iter().map(|h| *h.key).collect::<Vec<_>>();
+        assert_eq!(keys, vec!["a", "b", "e"]);
+        assert_eq!(hits[0].similarity, 1.0);
+        assert_eq!(hits[1].similarity, 0.75);
+
+        assert!(index.threshold(&query, 1.01).is_empty());
+        assert_eq!(index.threshold(&query, 0.0).len(), 5);
+    }
+
+    #[test]
+    fn index_extend() {
+        let fingerprints = [vec![1, 2, 3], vec![1], vec![4, 5, 6], vec![], vec![2]];
+        let mut inserted = FingerprintIndex::new();
+        for (i, bits) in fingerprints.iter().enumerate() {
+            inserted.insert(i, PubChemFingerprint::from_bits(bits.clone()));
+        }
+
+        let mut extended = FingerprintIndex::new();
+        extended.insert(0, PubChemFingerprint::from_bits(fingerprints[0].clone()));
+        extended.extend(
+            (1..fingerprints.len())
+                .map(|i| (i, PubChemFingerprint::from_bits(fingerprints[i].clone()))),
+        );
+
+        let keys = |index: &FingerprintIndex<usize>| {
+            index
+                .entries
+                .iter()
+                .map(|e| (e.key, e.index))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            keys(&extended),
+            vec![(3, 3), (1, 1), (4, 4), (0, 0), (2, 2)]
+        );
+        assert_eq!(keys(&extended), keys(&inserted));
+    }
+
+    #[test]
+    fn index_top_k() {
+        let mut index = FingerprintIndex::new().threads(3);
+        for i in 0..50 {
+            index.insert(i, PubChemFingerprint::from_bits(0..i));
+        }
+
+        let query = PubChemFingerprint::from_bits(0..20);
+        let hits = index.top_k(&query, 3);
+        let keys = hits.iter().map(|h| *h.key).collect::<Vec<_>>();
+        assert_eq!(keys, vec![20, 21, 19]);
+
+        let sequential =
+            FingerprintIndex::from_iter((0..50).map(|i| (i, PubChemFingerprint::from_bits(0..i))))
+                .threads(1);
+        assert_eq!(sequential.top_k(&query, 10), index.top_k(&query, 10));
+        assert_eq!(index.top_k(&query, 100).len(), 50);
+        assert!(index.top_k(&query, 0).is_empty());
+    }
+}
@@ -0,0 +1,1005 @@
+//! Typed extraction of GHS hazard classifications from PUG-View records.
+//!
+//! The *Globally Harmonized System of Classification and Labelling of
+//! Chemicals* (GHS) classification of a compound is deposited in PubChem by
+//! several sources, each with their own pictograms, signal word, hazard
+//! statements (H-statements) and precautionary statements (P-statements).
+//! This module parses the free-text annotations of the *GHS Classification*
+//! section of a [`Record`](crate::model::view::Record) into typed values.
+
+use std::collections::BTreeSet;
+
+use crate::model::view::Information;
+use crate::model::view::Record;
+use crate::model::view::Reference;
+
+/// The heading of the GHS classification section in PUG-View records.
+pub const HEADING: &str = "GHS Classification";
+
+named_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    /// A GHS hazard pictogram.
+    pub enum Pictogram {
+        /// Exploding bomb, for explosives and self-reactive substances.
+        GHS01,
+        /// Flame, for flammable and pyrophoric substances.
+        GHS02,
+        /// Flame over circle, for oxidizers.
+        GHS03,
+        /// Gas cylinder, for gases under pressure.
+        GHS04,
+        /// Corrosion, for corrosive substances.
+        GHS05,
+        /// Skull and crossbones, for acutely toxic substances.
+        GHS06,
+        /// Exclamation mark, for irritants and less severe hazards.
+        GHS07,
+        /// Health hazard, for carcinogens, mutagens and other chronic hazards.
+        GHS08,
+        /// Environment, for substances hazardous to the aquatic environment.
+        GHS09,
+    }
+}
+
+impl Pictogram {
+    /// Get the common description of the pictogram, as used by PubChem.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Pictogram::GHS01 => "Explosive",
+            Pictogram::GHS02 => "Flammable",
+            Pictogram::GHS03 => "Oxidizer",
+            Pictogram::GHS04 => "Compressed Gas",
+            Pictogram::GHS05 => "Corrosive",
+            Pictogram::GHS06 => "Acute Toxic",
+            Pictogram::GHS07 => "Irritant",
+            Pictogram::GHS08 => "Health Hazard",
+            Pictogram::GHS09 => "Environmental Hazard",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// A GHS signal word, indicating the relative severity of a hazard.
+///
+/// Signal words are ordered by severity, so that the most severe signal
+/// word of a list can be obtained with [`Iterator::max`].
+pub enum SignalWord {
+    /// Used for the less severe hazard categories.
+    Warning,
+    /// Used for the more severe hazard categories.
+    Danger,
+}
+
+impl SignalWord {
+    /// Get the signal word with the given name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("danger") {
+            Some(SignalWord::Danger)
+        } else if name.eq_ignore_ascii_case("warning") {
+            Some(SignalWord::Warning)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// The kind of hazard described by a hazard statement.
+pub enum HazardCategory {
+    /// Physical hazards (`H2xx`).
+    Physical,
+    /// Health hazards (`H3xx`).
+    Health,
+    /// Environmental hazards (`H4xx`).
+    Environmental,
+    /// Supplemental hazard information from the EU CLP regulation (`EUHxxx`).
+    Supplemental,
+}
+
+named_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    /// A GHS hazard statement code.
+    pub enum HazardCode {
+        /// Unstable explosive.
+        H200,
+        /// Explosive; mass explosion hazard.
+        H201,
+        /// Explosive; severe projection hazard.
+        H202,
+        /// Explosive; fire, blast or projection hazard.
+        H203,
+        /// Fire or projection hazard.
+        H204,
+        /// May mass explode in fire.
+        H205,
+        /// Fire, blast or projection hazard; increased risk of explosion if desensitizing agent is reduced.
+        H206,
+        /// Fire or projection hazard; increased risk of explosion if desensitizing agent is reduced.
+        H207,
+        /// Fire hazard; increased risk of explosion if desensitizing agent is reduced.
+        H208,
+        /// Explosive.
+        H209,
+        /// Very sensitive.
+        H210,
+        /// May be sensitive.
+        H211,
+        /// Extremely flammable gas.
+        H220,
+        /// Flammable gas.
+        H221,
+        /// Extremely flammable aerosol.
+        H222,
+        /// Flammable aerosol.
+        H223,
+        /// Extremely flammable liquid and vapour.
+        H224,
+        /// Highly flammable liquid and vapour.
+        H225,
+        /// Flammable liquid and vapour.
+        H226,
+        /// Combustible liquid.
+        H227,
+        /// Flammable solid.
+        H228,
+        /// Pressurized container: may burst if heated.
+        H229,
+        /// May react explosively even in the absence of air.
+        H230,
+        /// May react explosively even in the absence of air at elevated pressure and/or temperature.
+        H231,
+        /// May ignite spontaneously if exposed to air.
+        H232,
+        /// Heating may cause an explosion.
+        H240,
+        /// Heating may cause a fire or explosion.
+        H241,
+        /// Heating may cause a fire.
+        H242,
+        /// Catches fire spontaneously if exposed to air.
+        H250,
+        /// Self-heating; may catch fire.
+        H251,
+        /// Self-heating in large quantities; may catch fire.
+        H252,
+        /// In contact with water releases flammable gases which may ignite spontaneously.
+        H260,
+        /// In contact with water releases flammable gas.
+        H261,
+        /// May cause or intensify fire; oxidizer.
+        H270,
+        /// May cause fire or explosion; strong oxidizer.
+        H271,
+        /// May intensify fire; oxidizer.
+        H272,
+        /// Contains gas under pressure; may explode if heated.
+        H280,
+        /// Contains refrigerated gas; may cause cryogenic burns or injury.
+        H281,
+        /// Extremely flammable chemically unstable gas: may react explosively even in the absence of air.
+        H282,
+        /// Flammable chemically unstable gas: may react explosively even in the absence of air.
+        H283,
+        /// Chemically unstable gas: may react explosively even in the absence of air.
+        H284,
+        /// May be corrosive to metals.
+        H290,
+        /// Fatal if swallowed.
+        H300,
+        /// Toxic if swallowed.
+        H301,
+        /// Harmful if swallowed.
+        H302,
+        /// May be harmful if swallowed.
+        H303,
+        /// May be fatal if swallowed and enters airways.
+        H304,
+        /// May be harmful if swallowed and enters airways.
+        H305,
+        /// Fatal in contact with skin.
+        H310,
+        /// Toxic in contact with skin.
+        H311,
+        /// Harmful in contact with skin.
+        H312,
+        /// May be harmful in contact with skin.
+        H313,
+        /// Causes severe skin burns and eye damage.
+        H314,
+        /// Causes skin irritation.
+        H315,
+        /// Causes mild skin irritation.
+        H316,
+        /// May cause an allergic skin reaction.
+        H317,
+        /// Causes serious eye damage.
+        H318,
+        /// Causes serious eye irritation.
+        H319,
+        /// Causes eye irritation.
+        H320,
+        /// Fatal if inhaled.
+        H330,
+        /// Toxic if inhaled.
+        H331,
+        /// Harmful if inhaled.
+        H332,
+        /// May be harmful if inhaled.
+        H333,
+        /// May cause allergy or asthma symptoms or breathing difficulties if inhaled.
+        H334,
+        /// May cause respiratory irritation.
+        H335,
+        /// May cause drowsiness or dizziness.
+        H336,
+        /// May cause genetic defects.
+        H340,
+        /// Suspected of causing genetic defects.
+        H341,
+        /// May cause cancer.
+        H350,
+        /// May cause cancer by inhalation.
+        H350i,
+        /// Suspected of causing cancer.
+        H351,
+        /// May damage fertility or the unborn child.
+        H360,
+        /// May damage fertility.
+        H360F,
+        /// May damage the unborn child.
+        H360D,
+        /// May damage fertility. May damage the unborn child.
+        H360FD,
+        /// May damage fertility. Suspected of damaging the unborn child.
+        H360Fd,
+        /// May damage the unborn child. Suspected of damaging fertility.
+        H360Df,
+        /// Suspected of damaging fertility or the unborn child.
+        H361,
+        /// Suspected of damaging fertility.
+        H361f,
+        /// Suspected of damaging the unborn child.
+        H361d,
+        /// Suspected of damaging fertility. Suspected of damaging the unborn child.
+        H361fd,
+        /// May cause harm to breast-fed children.
+        H362,
+        /// Causes damage to organs.
+        H370,
+        /// May cause damage to organs.
+        H371,
+        /// Causes damage to organs through prolonged or repeated exposure.
+        H372,
+        /// May cause damage to organs through prolonged or repeated exposure.
+        H373,
+        /// Very toxic to aquatic life.
+        H400,
+        /// Toxic to aquatic life.
+        H401,
+        /// Harmful to aquatic life.
+        H402,
+        /// Very toxic to aquatic life with long lasting effects.
+        H410,
+        /// Toxic to aquatic life with long lasting effects.
+        H411,
+        /// Harmful to aquatic life with long lasting effects.
+        H412,
+        /// May cause long lasting harmful effects to aquatic life.
+        H413,
+        /// Harms public health and the environment by destroying ozone in the upper atmosphere.
+        H420,
+        /// Explosive when dry.
+        EUH001,
+        /// Explosive with or without contact with air.
+        EUH006,
+        /// Reacts violently with water.
+        EUH014,
+        /// In use may form flammable/explosive vapour-air mixture.
+        EUH018,
+        /// May form explosive peroxides.
+        EUH019,
+        /// Contact with water liberates toxic gas.
+        EUH029,
+        /// Contact with acids liberates toxic gas.
+        EUH031,
+        /// Contact with acids liberates very toxic gas.
+        EUH032,
+        /// Risk of explosion if heated under confinement.
+        EUH044,
+        /// Repeated exposure may cause skin dryness or cracking.
+        EUH066,
+        /// Toxic by eye contact.
+        EUH070,
+        /// Corrosive to the respiratory tract.
+        EUH071,
+        /// May cause endocrine disruption in humans.
+        EUH380,
+        /// Suspected of causing endocrine disruption in humans.
+        EUH381,
+        /// To avoid risks to human health and the environment, comply with the instructions for use.
+        EUH401,
+        /// May cause endocrine disruption in the environment.
+        EUH430,
+        /// Suspected of causing endocrine disruption in the environment.
+        EUH431,
+        /// Accumulates in the environment and living organisms including in humans.
+        EUH440,
+        /// Strongly accumulates in the environment and living organisms including in humans.
+        EUH441,
+        /// Can cause long-lasting and diffuse contamination of water resources.
+        EUH450,
+        /// Can cause very long-lasting and diffuse contamination of water resources.
+        EUH451,
+    }
+}
+
+impl HazardCode {
+    /// Get the kind of hazard described by this code.
+    pub fn category(&self) -> HazardCategory {
+        let name = self.name();
+        if name.starts_with("EUH") {
+            return HazardCategory::Supplemental;
+        }
+        match name.as_bytes()[1] {
+            b'2' => HazardCategory::Physical,
+            b'3' => HazardCategory::Health,
+            _ => HazardCategory::Environmental,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// The kind of measure described by a precautionary statement.
+pub enum PrecautionaryCategory {
+    /// General precautionary statements (`P1xx`).
+    General,
+    /// Prevention precautionary statements (`P2xx`).
+    Prevention,
+    /// Response precautionary statements (`P3xx`).
+    Response,
+    /// Storage precautionary statements (`P4xx`).
+    Storage,
+    /// Disposal precautionary statements (`P5xx`).
+    Disposal,
+}
+
+named_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    /// A GHS precautionary statement code.
+    pub enum PrecautionaryCode {
+        /// If medical advice is needed, have product container or label at hand.
+        P101,
+        /// Keep out of reach of children.
+        P102,
+        /// Read carefully and follow all instructions.
+        P103,
+        /// Obtain special instructions before use.
+        P201,
+        /// Do not handle until all safety precautions have been read and understood.
+        P202,
+        /// Obtain, read and follow all safety instructions before use.
+        P203,
+        /// Keep away from heat, hot surfaces, sparks, open flames and other ignition sources. No smoking.
+        P210,
+        /// Do not spray on an open flame or other ignition source.
+        P211,
+        /// Avoid heating under confinement or reduction of the desensitizing agent.
+        P212,
+        /// Keep away from clothing and other combustible materials.
+        P220,
+        /// Take any precaution to avoid mixing with combustibles.
+        P221,
+        /// Do not allow contact with air.
+        P222,
+        /// Do not allow contact with water.
+        P223,
+        /// Keep wetted with an appropriate material.
+        P230,
+        /// Handle and store contents under inert gas.
+        P231,
+        /// Protect from moisture.
+        P232,
+        /// Keep container tightly closed.
+        P233,
+        /// Keep only in original packaging.
+        P234,
+        /// Keep cool.
+        P235,
+        /// Keep only in original packaging in the transport configuration.
+        P236,
+        /// Ground and bond container and receiving equipment.
+        P240,
+        /// Use explosion-proof electrical, ventilating and lighting equipment.
+        P241,
+        /// Use non-sparking tools.
+        P242,
+        /// Take action to prevent static discharges.
+        P243,
+        /// Keep valves and fittings free from oil and grease.
+        P244,
+        /// Do not subject to grinding, shock or friction.
+        P250,
+        /// Do not pierce or burn, even after use.
+        P251,
+        /// Do not breathe dust, fume, gas, mist, vapours or spray.
+        P260,
+        /// Avoid breathing dust, fume, gas, mist, vapours or spray.
+        P261,
+        /// Do not get in eyes, on skin, or on clothing.
+        P262,
+        /// Avoid contact during pregnancy and while nursing.
+        P263,
+        /// Wash hands thoroughly after handling.
+        P264,
+        /// Do not touch eyes.
+        P265,
+        /// Do not eat, drink or smoke when using this product.
+        P270,
+        /// Use only outdoors or in a well-ventilated area.
+        P271,
+        /// Contaminated work clothing should not be allowed out of the workplace.
+        P272,
+        /// Avoid release to the environment.
+        P273,
+        /// Wear protective gloves, protective clothing, eye protection and face protection.
+        P280,
+        /// Use personal protective equipment as required.
+        P281,
+        /// Wear cold insulating gloves and either face shield or eye protection.
+        P282,
+        /// Wear fire resistant or flame retardant clothing.
+        P283,
+        /// In case of inadequate ventilation wear respiratory protection.
+        P284,
+        /// In case of inadequate ventilation wear respiratory protection (superseded by P284).
+        P285,
+        /// IF SWALLOWED:
+        P301,
+        /// IF ON SKIN:
+        P302,
+        /// IF ON SKIN (or hair):
+        P303,
+        /// IF INHALED:
+        P304,
+        /// IF IN EYES:
+        P305,
+        /// IF ON CLOTHING:
+        P306,
+        /// IF exposed:
+        P307,
+        /// IF exposed or concerned:
+        P308,
+        /// IF exposed or if you feel unwell:
+        P309,
+        /// Immediately call a POISON CENTER or doctor.
+        P310,
+        /// Call a POISON CENTER or doctor.
+        P311,
+        /// Call a POISON CENTER or doctor if you feel unwell.
+        P312,
+        /// Get medical advice or attention.
+        P313,
+        /// Get medical advice or attention if you feel unwell.
+        P314,
+        /// Get immediate medical advice or attention.
+        P315,
+        /// Get emergency medical help immediately.
+        P316,
+        /// Get medical help.
+        P317,
+        /// If exposed or concerned, get medical advice.
+        P318,
+        /// Get medical help if you feel unwell.
+        P319,
+        /// Specific treatment is urgent (see label).
+        P320,
+        /// Specific treatment (see label).
+        P321,
+        /// Specific measures (see label).
+        P322,
+        /// Rinse mouth.
+        P330,
+        /// Do NOT induce vomiting.
+        P331,
+        /// If skin irritation occurs:
+        P332,
+        /// If skin irritation or rash occurs:
+        P333,
+        /// Immerse in cool water or wrap in wet bandages.
+        P334,
+        /// Brush off loose particles from skin.
+        P335,
+        /// Thaw frosted parts with lukewarm water. Do not rub affected area.
+        P336,
+        /// If eye irritation persists:
+        P337,
+        /// Remove contact lenses, if present and easy to do. Continue rinsing.
+        P338,
+        /// Remove person to fresh air and keep comfortable for breathing.
+        P340,
+        /// If breathing is difficult, remove person to fresh air and keep at rest.
+        P341,
+        /// If experiencing respiratory symptoms:
+        P342,
+        /// Gently wash with plenty of soap and water.
+        P350,
+        /// Rinse cautiously with water for several minutes.
+        P351,
+        /// Wash with plenty of water.
+        P352,
+        /// Rinse skin with water or shower.
+        P353,
+        /// Immediately rinse with water for several minutes.
+        P354,
+        /// Rinse immediately contaminated clothing and skin with plenty of water before removing clothes.
+        P360,
+        /// Take off immediately all contaminated clothing.
+        P361,
+        /// Take off contaminated clothing.
+        P362,
+        /// Wash contaminated clothing before reuse.
+        P363,
+        /// And wash it before reuse.
+        P364,
+        /// In case of fire:
+        P370,
+        /// In case of major fire and large quantities:
+        P371,
+        /// Explosion risk.
+        P372,
+        /// DO NOT fight fire when fire reaches explosives.
+        P373,
+        /// Fight fire with normal precautions from a reasonable distance.
+        P374,
+        /// Fight fire remotely due to the risk of explosion.
+        P375,
+        /// Stop leak if safe to do so.
+        P376,
+        /// Leaking gas fire: Do not extinguish, unless leak can be stopped safely.
+        P377,
+        /// Use appropriate media to extinguish.
+        P378,
+        /// Evacuate area.
+        P380,
+        /// In case of leakage, eliminate all ignition sources.
+        P381,
+        /// Absorb spillage to prevent material damage.
+        P390,
+        /// Collect spillage.
+        P391,
+        /// Store in accordance with local regulations.
+        P401,
+        /// Store in a dry place.
+        P402,
+        /// Store in a well-ventilated place.
+        P403,
+        /// Store in a closed container.
+        P404,
+        /// Store locked up.
+        P405,
+        /// Store in a corrosion resistant container with a resistant inner liner.
+        P406,
+        /// Maintain air gap between stacks or pallets.
+        P407,
+        /// Protect from sunlight.
+        P410,
+        /// Store at temperatures not exceeding the specified limit.
+        P411,
+        /// Do not expose to temperatures exceeding 50 °C/122 °F.
+        P412,
+        /// Store bulk masses at temperatures not exceeding the specified limit.
+        P413,
+        /// Store separately.
+        P420,
+        /// Store contents under an appropriate medium.
+        P422,
+        /// Dispose of contents and container in accordance with local regulations.
+        P501,
+        /// Refer to manufacturer or supplier for information on recovery or recycling.
+        P502,
+        /// Refer to manufacturer or supplier for information on disposal, recovery or recycling.
+        P503,
+    }
+}
+
+impl PrecautionaryCode {
+    /// Get the kind of measure described by this code.
+    pub fn category(&self) -> PrecautionaryCategory {
+        match self.name().as_bytes()[1] {
+            b'1' => PrecautionaryCategory::General,
+            b'2' => PrecautionaryCategory::Prevention,
+            b'3' => PrecautionaryCategory::Response,
+            b'4' => PrecautionaryCategory::Storage,
+            _ => PrecautionaryCategory::Disposal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A hazard statement reported by a source.
+pub struct HazardStatement {
+    /// The statement code as reported, possibly combined (e.g. `H300+H310`).
+    pub code: String,
+    /// The individual codes of the statement.
+    ///
+    /// *Codes unknown to this library are omitted, but are still available
+    /// in the [`code`](HazardStatement::code) field.*
+    pub codes: Vec<HazardCode>,
+    /// The text of the statement.
+    pub text: String,
+    /// The percentage of notifications reporting this statement, if given.
+    pub percentage: Option<f64>,
+    /// The signal word associated with the statement, if given.
+    pub signal_word: Option<SignalWord>,
+    /// The hazard class and category of the statement, if given.
+    pub hazard_class: Option<String>,
+}
+
+impl HazardStatement {
+    /// Parse a hazard statement from its PubChem textual representation.
+    ///
+    /// Statements are formatted as `H302 (99.5%): Harmful if swallowed
+    /// [Warning Acute toxicity, oral]`, where the percentage and the bracketed
+    /// classification are optional.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let end = s.find([' ', ':']).unwrap_or(s.len());
+        let code = &s[..end];
+        let codes = parse_codes(code, "H", HazardCode::from_name)?;
+
+        let mut rest = s[end..].trim_start();
+        let mut percentage = None;
+        if let Some(stripped) = rest.strip_prefix('(') {
+            let close = stripped.find(')')?;
+            percentage = stripped[..close].trim_end_matches('%').trim().parse().ok();
+            rest = stripped[close + 1..].trim_start();
+        }
+        let rest = rest.strip_prefix(':').unwrap_or(rest).trim();
+
+        let (text, classification) = match (rest.rfind('['), rest.ends_with(']')) {
+            (Some(open), true) => (rest[..open].trim(), Some(&rest[open + 1..rest.len() - 1])),
+            _ => (rest, None),
+        };
+        let (signal_word, hazard_class) = match classification {
+            None => (None, None),
+            Some(c) => match c.split_once(' ') {
+                Some((word, class)) if SignalWord::from_name(word).is_some() => {
+                    (SignalWord::from_name(word), Some(class.trim().to_string()))
+                }
+                _ => (None, Some(c.trim().to_string())),
+            },
+        };
+
+        Some(HazardStatement {
+            code: code.to_string(),
+            codes,
+            text: text.to_string(),
+            percentage,
+            signal_word,
+            hazard_class,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A precautionary statement reported by a source.
+pub struct PrecautionaryStatement {
+    /// The statement code as reported, possibly combined (e.g. `P301+P310`).
+    pub code: String,
+    /// The individual codes of the statement.
+    ///
+    /// *Codes unknown to this library are omitted, but are still available
+    /// in the [`code`](PrecautionaryStatement::code) field.*
+    pub codes: Vec<PrecautionaryCode>,
+}
+
+impl PrecautionaryStatement {
+    /// Parse all precautionary statements from a list of codes.
+    ///
+    /// PubChem reports precautionary statements as a list of codes such as
+    /// `P264, P270, P301+P317, P330, and P501`, followed by a note.
+    pub fn parse_list(s: &str) -> Vec<Self> {
+        s.split(|c: char| !(c.is_ascii_alphanumeric() || c == '+'))
+            .filter_map(|token| {
+                let codes = parse_codes(token, "P", PrecautionaryCode::from_name)?;
+                Some(PrecautionaryStatement {
+                    code: token.to_string(),
+                    codes,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parse a (possibly combined) statement code into its individual codes.
+///
+/// Returns `None` if the token does not look like a statement code with the
+/// given prefix, so that surrounding prose can be skipped.
+fn parse_codes<T, F>(token: &str, prefix: &str, from_name: F) -> Option<Vec<T>>
+where
+    F: Fn(&str) -> Option<T>,
+{
+    let looks_like_code = |part: &str| {
+        let digits = part
+            .strip_prefix(prefix)
+            .or_else(|| part.strip_prefix("EUH"))
+            .unwrap_or("");
+        digits.len() >= 3 && digits.as_bytes()[..3].iter().all(u8::is_ascii_digit)
+    };
+    let parts = token.split('+').collect::<Vec<_>>();
+    if parts.is_empty() || !parts.iter().all(|p| looks_like_code(p)) {
+        return None;
+    }
+    Some(parts.into_iter().filter_map(from_name).collect())
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// The GHS classification of a compound reported by a single source.
+pub struct GhsEntry {
+    /// The reference to the source of the classification, if available.
+    pub reference: Option<Reference>,
+    /// The hazard pictograms.
+    pub pictograms: Vec<Pictogram>,
+    /// The signal word.
+    pub signal_word: Option<SignalWord>,
+    /// The hazard statements.
+    pub hazard_statements: Vec<HazardStatement>,
+    /// The precautionary statements.
+    pub precautionary_statements: Vec<PrecautionaryStatement>,
+}
+
+impl GhsEntry {
+    /// Update the entry with the content of a PUG-View information.
+    fn add_information(&mut self, information: &Information) {
+        let strings = &information.value.string_with_markup;
+        match information.name.as_deref() {
+            Some("Pictogram(s)") => {
+                let pictograms = strings
+                    .iter()
+                    .flat_map(|s| s.markup.iter())
+                    .filter_map(|m| m.url.as_deref())
+                    .filter_map(|url| url.rsplit('/').next())
+                    .filter_map(|file| file.split('.').next())
+                    .filter_map(Pictogram::from_name);
+                self.pictograms.extend(pictograms);
+            }
+            Some("Signal") => {
+                self.signal_word = strings
+                    .iter()
+                    .find_map(|s| SignalWord::from_name(s.string.trim()));
+            }
+            Some("GHS Hazard Statements") => {
+                let statements = strings
+                    .iter()
+                    .filter_map(|s| HazardStatement::parse(&s.string));
+                self.hazard_statements.extend(statements);
+            }
+            Some("Precautionary Statement Codes") => {
+                let statements = strings
+                    .iter()
+                    .flat_map(|s| PrecautionaryStatement::parse_list(&s.string));
+                self.precautionary_statements.extend(statements);
+            }
+            _ => (),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// The GHS classification of a compound, as reported by all sources.
+pub struct GhsClassification {
+    /// The classifications of each source, in the order reported by PubChem.
+    pub entries: Vec<GhsEntry>,
+}
+
+impl GhsClassification {
+    /// Extract the GHS classification from a PUG-View record.
+    ///
+    /// Information is grouped into entries by reference number, so that each
+    /// entry corresponds to a single source. Records without a GHS
+    /// classification section produce an empty classification.
+    pub fn from_record(record: &Record) -> Self {
+        let mut entries: Vec<(Option<i32>, GhsEntry)> = Vec::new();
+        if let Some(section) = record.section(HEADING) {
+            for information in section.all_information() {
+                let number = information.reference_number;
+                let index = match entries.iter().position(|(n, _)| *n == number) {
+                    Some(index) => index,
+                    None => {
+                        let reference = number.and_then(|n| record.reference(n)).cloned();
+                        let entry = GhsEntry {
+                            reference,
+                            ..Default::default()
+                        };
+                        entries.push((number, entry));
+                        entries.len() - 1
+                    }
+                };
+                entries[index].1.add_information(information);
+            }
+        }
+        GhsClassification {
+            entries: entries.into_iter().map(|(_, entry)| entry).collect(),
+        }
+    }
+
+    /// Check whether no source reported a GHS classification.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the pictograms reported by any source.
+    pub fn pictograms(&self) -> BTreeSet<Pictogram> {
+        self.entries
+            .iter()
+            .flat_map(|e| e.pictograms.iter().cloned())
+            .collect()
+    }
+
+    /// Get the most severe signal word reported by any source.
+    pub fn signal_word(&self) -> Option<SignalWord> {
+        self.entries.iter().filter_map(|e| e.signal_word).max()
+    }
+
+    /// Get the hazard codes reported by any source.
+    pub fn hazard_codes(&self) -> BTreeSet<HazardCode> {
+        self.entries
+            .iter()
+            .flat_map(|e| e.hazard_statements.iter())
+            .flat_map(|s| s.codes.iter().cloned())
+            .collect()
+    }
+
+    /// Get the precautionary codes reported by any source.
+    pub fn precautionary_codes(&self) -> BTreeSet<PrecautionaryCode> {
+        self.entries
+            .iter()
+            .flat_map(|e| e.precautionary_statements.iter())
+            .flat_map(|s| s.codes.iter().cloned())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::model::view::Markup;
+    use crate::model::view::Section;
+    use crate::model::view::StringWithMarkup;
+    use crate::model::view::Value;
+
+    fn information(reference: i32, name: &str, strings: &[&str]) -> Information {
+        Information {
+            reference_number: Some(reference),
+            name: Some(name.to_string()),
+            value: Value {
+                string_with_markup: strings
+                    .iter()
+                    .map(|s| StringWithMarkup {
+                        string: s.to_string(),
+                        markup: Vec::new(),
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn hazard_statement_parse() {
+        let s = HazardStatement::parse(
+            "H302 (99.5%): Harmful if swallowed [Warning Acute toxicity, oral]",
+        )
+        .unwrap();
+        assert_eq!(s.codes, vec![HazardCode::H302]);
+        assert_eq!(s.text, "Harmful if swallowed");
+        assert_eq!(s.percentage, Some(99.5));
+        assert_eq!(s.signal_word, Some(SignalWord::Warning));
+        assert_eq!(s.hazard_class.as_deref(), Some("Acute toxicity, oral"));
+        assert_eq!(s.codes[0].category(), HazardCategory::Health);
+
+        let s = HazardStatement::parse("H300+H310: Fatal if swallowed or in contact with skin")
+            .unwrap();
+        assert_eq!(s.code, "H300+H310");
+        assert_eq!(s.codes, vec![HazardCode::H300, HazardCode::H310]);
+        assert_eq!(s.percentage, None);
+        assert_eq!(s.signal_word, None);
+
+        assert!(HazardStatement::parse("Reported as not meeting GHS hazard criteria").is_none());
+    }
+
+    #[test]
+    fn precautionary_statement_parse_list() {
+        let statements = PrecautionaryStatement::parse_list(
+            "P264, P270, P301+P317, P330, and P501 (The corresponding statement to each P-code can be found at the GHS Classification page.)"
+        );
+        let codes = statements
+            .iter()
+            .map(|s| s.code.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(codes, vec!["P264", "P270", "P301+P317", "P330", "P501"]);
+        assert_eq!(
+            statements[2].codes,
+            vec![PrecautionaryCode::P301, PrecautionaryCode::P317]
+        );
+        assert_eq!(
+            statements[4].codes[0].category(),
+            PrecautionaryCategory::Disposal
+        );
+    }
+
+    #[test]
+    fn classification_from_record() {
+        let mut pictograms = information(1, "Pictogram(s)", &[" "]);
+        pictograms.value.string_with_markup[0].markup = vec![Markup {
+            url: Some(String::from(
+                "https://pubchem.ncbi.nlm.nih.gov/images/ghs/GHS07.svg",
+            )),
+            ty: Some(String::from("Icon")),
+            extra: Some(String::from("Irritant")),
+            ..Default::default()
+        }];
+        let record = Record {
+            record_type: String::from("CID"),
+            record_number: 2244,
+            sections: vec![Section {
+                toc_heading: String::from("Safety and Hazards"),
+                sections: vec![Section {
+                    toc_heading: String::from(HEADING),
+                    information: vec![
+                        pictograms,
+                        information(1, "Signal", &["Warning"]),
+                        information(1, "GHS Hazard Statements", &[
+                            "H302 (100%): Harmful if swallowed [Warning Acute toxicity, oral]",
+                        ]),
+                        information(1, "Precautionary Statement Codes", &["P264, P270, and P501"]),
+                        information(2, "Signal", &["Danger"]),
+                        information(2, "GHS Hazard Statements", &[
+                            "H318: Causes serious eye damage [Danger Serious eye damage/eye irritation]",
+                        ]),
+                    ],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            references: vec![Reference {
+                reference_number: Some(1),
+                source_name: Some(String::from("European Chemicals Agency (ECHA)")),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let ghs = GhsClassification::from_record(&record);
+        assert_eq!(ghs.entries.len(), 2);
+        assert_eq!(
+            ghs.entries[0]
+                .reference
+                .as_ref()
+                .unwrap()
+                .source_name
+                .as_deref(),
+            Some("European Chemicals Agency (ECHA)")
+        );
+        assert!(ghs.entries[1].reference.is_none());
+        assert_eq!(
+            ghs.pictograms().into_iter().collect::<Vec<_>>(),
+            vec![Pictogram::GHS07]
+        );
+        assert_eq!(ghs.signal_word(), Some(SignalWord::Danger));
+        assert_eq!(
+            ghs.hazard_codes().into_iter().collect::<Vec<_>>(),
+            vec![HazardCode::H302, HazardCode::H318]
+        );
+        assert_eq!(ghs.precautionary_codes().len(), 3);
+    }
+
+    #[test]
+    fn classification_from_record_empty() {
+        let ghs = GhsClassification::from_record(&Record::default());
+        assert!(ghs.is_empty());
+        assert_eq!(ghs.signal_word(), None);
+    }
+}
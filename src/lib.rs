@@ -11,17 +11,182 @@ mod parser;
 mod utils;
 
 pub mod error;
+pub mod experimental;
+pub mod fingerprint;
+pub mod ghs;
 pub mod model;
+pub mod resolve;
 
 use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering as AtomicOrdering;
+use std::time::Duration;
+use std::time::SystemTime;
 
+use self::error::ApiError;
 use self::error::Error;
+use self::error::ErrorContext;
 use self::model::rest;
+use self::model::view;
+use self::parser::ElementStream;
 use self::parser::FromApiResponse;
+use self::parser::FromXml;
 use self::utils::Join;
 
+pub use self::parser::ParseMode;
+
+/// The base URL of the Power User Gateway REST API.
+const PUG_REST: &str = "https://pubchem.ncbi.nlm.nih.gov/rest/pug";
+
+/// The base URL of the PUG-View API.
+const PUG_VIEW: &str = "https://pubchem.ncbi.nlm.nih.gov/rest/pug_view";
+
+/// Check the response of the REST API for errors.
+///
+/// HTTP error responses are decoded into an [`ApiError`](error::ApiError),
+/// annotated with the status code and the description of the request. Other
+/// errors of the HTTP client are returned as-is.
+fn check_response<F>(
+    result: Result<ureq::Response, ureq::Error>,
+    describe: F,
+) -> Result<ureq::Response, Error>
+where
+    F: FnOnce() -> String,
+{
+    match result {
+        Err(ureq::Error::Status(status, response)) => {
+            let mut error = api_error(status, response)?;
+            error.context_mut().request = Some(describe());
+            Err(Error::Api(error))
+        }
+        Err(e) => Err(Error::from(e)),
+        Ok(response) => Ok(response),
+    }
+}
+
+/// Decode an HTTP error response into an API error.
+///
+/// The body of the response is expected to be a PubChem fault, but error
+/// pages from intermediate proxies are handled as well: the error is then
+/// chosen from the status code, and a snippet of the body is given as the
+/// error details. A `429 Too Many Requests` status always gives a
+/// [`ApiError::Throttled`](error::ApiError::Throttled) error.
+fn api_error(status: u16, response: ureq::Response) -> Result<ApiError, Error> {
+    let retry_after = response
+        .header("Retry-After")
+        .and_then(|value| utils::parse_retry_after(value, SystemTime::now()));
+    let status_text = response.status_text().to_string();
+
+    let mut body = Vec::new();
+    response.into_reader().read_to_end(&mut body)?;
+    let fault = parser::from_document::<rest::Fault>(body.clone(), b"Fault", ParseMode::Lenient)
+        .unwrap_or(None);
+
+    let mut error = match fault {
+        Some(fault) if status != 429 => ApiError::from(fault),
+        Some(fault) => ApiError::from_status(
+            status,
+            ErrorContext {
+                message: fault.message,
+                details: fault.details,
+                ..Default::default()
+            },
+        ),
+        None => {
+            let body = String::from_utf8_lossy(&body);
+            let snippet = utils::truncate(body.trim(), 200);
+            let mut context = ErrorContext::from(status_text);
+            if !snippet.is_empty() {
+                context.details.push(snippet.into_owned());
+            }
+            ApiError::from_status(status, context)
+        }
+    };
+
+    let context = error.context_mut();
+    context.status = Some(status);
+    context.retry_after = retry_after;
+    Ok(error)
+}
+
+/// Describe a request in error reports.
+///
+/// The base URL of the API is omitted, and long identifier lists are
+/// truncated so that errors of batch requests remain readable.
+fn describe_request(url: &str, input: Option<(&str, &str)>) -> String {
+    let path = url
+        .strip_prefix(PUG_REST)
+        .or_else(|| url.strip_prefix(PUG_VIEW))
+        .unwrap_or(url)
+        .trim_start_matches('/');
+    match input {
+        None => path.to_string(),
+        Some((namespace, identifiers)) => {
+            format!(
+                "{} ({}: {})",
+                path,
+                namespace,
+                utils::truncate(identifiers, 64)
+            )
+        }
+    }
+}
+
+/// Send the input identifiers to the REST API with a `POST` request.
+///
+/// Identifiers are sent URL-encoded, except for SDF records which PubChem
+/// only accepts as `multipart/form-data`. For namespaces with a path (such
+/// as `xref/RN`), the last path component is used as the form key.
+fn post(url: &str, namespace: &str, identifiers: &str) -> Result<ureq::Response, Error> {
+    post_as(url, "application/xml", namespace, identifiers)
+}
+
+/// Send the input identifiers with a `POST` request, accepting the given media type.
+fn post_as(
+    url: &str,
+    accept: &str,
+    namespace: &str,
+    identifiers: &str,
+) -> Result<ureq::Response, Error> {
+    let request = ureq::post(url).set("Accept", accept);
+    let describe = || describe_request(url, Some((namespace, identifiers)));
+    if namespace == "sdf" {
+        let (boundary, body) = utils::multipart(namespace, identifiers);
+        let content_type = format!("multipart/form-data; boundary={}", boundary);
+        check_response(
+            request
+                .set("Content-Type", &content_type)
+                .send_string(&body),
+            describe,
+        )
+    } else {
+        let key = namespace.rsplit('/').next().unwrap_or(namespace);
+        let form_data = form_urlencoded::Serializer::new(String::new())
+            .append_pair(key, identifiers)
+            .finish();
+        check_response(
+            request
+                .set("Content-Type", "application/x-www-form-urlencoded")
+                .send_string(&form_data),
+            describe,
+        )
+    }
+}
+
+/// Query the REST API with a `GET` request.
+fn get(url: &str) -> Result<ureq::Response, Error> {
+    check_response(
+        ureq::get(url).set("Accept", "application/xml").call(),
+        || describe_request(url, None),
+    )
+}
+
 named_enum! {
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     /// A single property that can be retrieved from a compound.
     pub enum CompoundProperty {
         /// Molecular formula.
@@ -109,60 +274,333 @@ named_enum! {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+/// The typed value of a single [`CompoundProperty`].
+pub enum PropertyValue {
+    /// A textual property, such as a SMILES string or an IUPAC name.
+    String(String),
+    /// A count or integral property.
+    Integer(i32),
+    /// A computed floating-point property.
+    Float(f64),
+    /// A mass, keeping its exact decimal representation.
+    Decimal(rest::Decimal),
+    /// A decoded substructure fingerprint.
+    Fingerprint(fingerprint::PubChemFingerprint),
+}
+
+impl PropertyValue {
+    /// Get the value as a floating-point number, if it is numeric.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            PropertyValue::Integer(i) => Some(*i as f64),
+            PropertyValue::Float(f) => Some(*f),
+            PropertyValue::Decimal(d) => Some(d.value()),
+            _ => None,
+        }
+    }
+
+    /// Get the value as a string slice, if it is textual.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            PropertyValue::String(s) => Some(s.as_str()),
+            _ => None,
+        }
+    }
+}
+
+named_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    /// A type of cross-reference to an external database.
+    pub enum XrefType {
+        /// Registry identifier given by the depositor.
+        RegistryID,
+        /// CAS Registry Number.
+        RN,
+        /// PubMed identifier of an article.
+        PubMedID,
+        /// Molecular Modeling Database identifier of a 3D structure.
+        MMDBID,
+        /// Database URL of the depositor.
+        DBURL,
+        /// Substance URL of the depositor.
+        SBURL,
+        /// NCBI GenInfo identifier of a protein.
+        ProteinGI,
+        /// NCBI GenInfo identifier of a nucleotide sequence.
+        NucleotideGI,
+        /// NCBI Taxonomy identifier of an organism.
+        TaxonomyID,
+        /// Online Mendelian Inheritance in Man identifier.
+        MIMID,
+        /// NCBI Gene identifier.
+        GeneID,
+        /// Probe identifier from the NCBI Probe database.
+        ProbeID,
+        /// Patent identifier.
+        PatentID,
+        /// Name of the depositor.
+        SourceName,
+        /// Category of the depositor.
+        SourceCategory,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The way compound names are matched by PubChem.
+pub enum NameType {
+    /// Match the complete name of the compound (the default).
+    Complete,
+    /// Match any word of the name of the compound.
+    Word,
+}
+
+impl NameType {
+    /// Get the value of the `name_type` parameter for this type.
+    fn name(&self) -> &'static str {
+        match self {
+            NameType::Complete => "complete",
+            NameType::Word => "word",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The compounds related to the input returned when retrieving Compound IDs.
+pub enum CidsType {
+    /// The compounds given as input.
+    Original,
+    /// The standardized forms of the input compounds.
+    Standardized,
+    /// The parent compounds, such as the neutral form of a salt.
+    Parent,
+    /// The individual components of mixtures and salts.
+    Component,
+    /// The preferred compounds, for input compounds that are deprecated.
+    Preferred,
+    /// The compounds with a similar 2D structure.
+    Similar2D,
+    /// The compounds with a similar 3D conformation.
+    Similar3D,
+    /// The compounds with the same stereochemistry and isotopes.
+    SameStereo,
+    /// The compounds with the same isotopes, ignoring stereochemistry.
+    SameIsotopes,
+    /// The compounds with the same connectivity, ignoring stereochemistry and isotopes.
+    SameConnectivity,
+    /// The compounds with the same tautomer.
+    SameTautomer,
+    /// The compounds with the same parent compound.
+    SameParent,
+    /// The compounds whose parent has the same stereochemistry and isotopes.
+    SameParentStereo,
+    /// The compounds whose parent has the same isotopes.
+    SameParentIsotopes,
+    /// The compounds whose parent has the same connectivity.
+    SameParentConnectivity,
+    /// The compounds whose parent has the same tautomer.
+    SameParentTautomer,
+}
+
+impl CidsType {
+    /// Get the value of the `cids_type` parameter for this type.
+    fn name(&self) -> &'static str {
+        match self {
+            CidsType::Original => "original",
+            CidsType::Standardized => "standardized",
+            CidsType::Parent => "parent",
+            CidsType::Component => "component",
+            CidsType::Preferred => "preferred",
+            CidsType::Similar2D => "similar_2d",
+            CidsType::Similar3D => "similar_3d",
+            CidsType::SameStereo => "same_stereo",
+            CidsType::SameIsotopes => "same_isotopes",
+            CidsType::SameConnectivity => "same_connectivity",
+            CidsType::SameTautomer => "same_tautomer",
+            CidsType::SameParent => "same_parent",
+            CidsType::SameParentStereo => "same_parent_stereo",
+            CidsType::SameParentIsotopes => "same_parent_isotopes",
+            CidsType::SameParentConnectivity => "same_parent_connectivity",
+            CidsType::SameParentTautomer => "same_parent_tautomer",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A type of date that can be retrieved for a record.
+pub enum DateType {
+    /// The date the record was first deposited.
+    Deposition,
+    /// The date the record was last modified.
+    Modification,
+    /// The date the record was publicly released.
+    Hold,
+    /// The date the record was created.
+    Creation,
+}
+
+impl DateType {
+    /// Get the value of the `dates_type` parameter for this type.
+    fn name(&self) -> &'static str {
+        match self {
+            DateType::Deposition => "deposition",
+            DateType::Modification => "modification",
+            DateType::Hold => "hold",
+            DateType::Creation => "creation",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A textual description of a compound, as provided by a PubChem depositor.
+pub struct Description {
+    /// The name of the source of the description.
+    pub source: Option<String>,
+    /// The text of the description.
+    pub text: String,
+    /// The URL of the description on the source website.
+    pub url: Option<String>,
+}
+
+impl Description {
+    /// Group the descriptions of an information list by compound ID.
+    ///
+    /// PubChem returns an `Information` record with the title of each
+    /// compound, followed by one record per description, so compounds
+    /// without any description are still listed.
+    fn from_information_list(list: rest::InformationList) -> Vec<(i32, Vec<Self>)> {
+        let mut grouped: Vec<(i32, Vec<Self>)> = Vec::new();
+        for information in list.informations {
+            let cid = information.cids.first().cloned().unwrap_or(information.id);
+            if grouped.last().map(|(c, _)| *c != cid).unwrap_or(true) {
+                grouped.push((cid, Vec::new()));
+            }
+            if let (Some(text), Some((_, descriptions))) =
+                (information.description, grouped.last_mut())
+            {
+                descriptions.push(Description {
+                    source: information.description_source_name,
+                    text,
+                    url: information.description_url,
+                });
+            }
+        }
+        grouped
+    }
+}
+
 #[derive(Debug)]
 /// A client for retrieving information about a single PubChem compound.
 pub struct Compound {
     namespace: Cow<'static, str>,
     identifier: Cow<'static, str>,
+    name_type: Option<NameType>,
+    cids_type: Option<CidsType>,
+    parse_mode: ParseMode,
 }
 
 impl Compound {
     /// Create a new `Compound` from the given compound ID.
     pub fn new(id: u32) -> Self {
-        Self {
-            namespace: Cow::Borrowed("cid"),
-            identifier: Cow::Owned(id.to_string()),
-        }
+        Self::with_namespace("cid", id.to_string())
     }
 
     /// Create a new `Compound` matching the given compound name.
     pub fn with_name(name: &str) -> Self {
-        Self {
-            namespace: Cow::Borrowed("name"),
-            identifier: Cow::Owned(name.to_string()),
-        }
+        Self::with_namespace("name", name.to_string())
     }
 
     /// Create a new `Compound` matching the given SMILES.
     pub fn with_smiles(smiles: &str) -> Self {
-        Self {
-            namespace: Cow::Borrowed("smiles"),
-            identifier: Cow::Owned(smiles.to_string()),
-        }
+        Self::with_namespace("smiles", smiles.to_string())
     }
 
     /// Create a new `Compound` matching the given InChI.
     pub fn with_inchi(inchi: &str) -> Self {
-        Self {
-            namespace: Cow::Borrowed("inchi"),
-            identifier: Cow::Owned(inchi.to_string()),
-        }
+        Self::with_namespace("inchi", inchi.to_string())
     }
 
     /// Create a new `Compound` matching the given InChIKey.
     pub fn with_inchikey(inchikey: &str) -> Self {
+        Self::with_namespace("inchikey", inchikey.to_string())
+    }
+
+    /// Create a new `Compound` matching the structure in the given SDF record.
+    ///
+    /// The MOL block of the record is checked locally before any request is
+    /// made, so that malformed inputs are reported with a [`ParseError`].
+    ///
+    /// [`ParseError`]: error::ParseError
+    pub fn with_sdf(sdf: &str) -> Result<Self, Error> {
+        utils::validate_sdf(sdf)?;
+        Ok(Self::with_namespace("sdf", sdf.to_string()))
+    }
+
+    fn with_namespace<N>(namespace: N, identifier: String) -> Self
+    where
+        N: Into<Cow<'static, str>>,
+    {
         Self {
-            namespace: Cow::Borrowed("inchikey"),
-            identifier: Cow::Owned(inchikey.to_string()),
+            namespace: namespace.into(),
+            identifier: Cow::Owned(identifier),
+            name_type: None,
+            cids_type: None,
+            parse_mode: ParseMode::Lenient,
         }
     }
 
-    // pub fn with_sdf(sdf: &str) -> Self {
-    //     Self {
-    //         namespace: Cow::Borrowed("sdf"),
-    //         identifiers: Cow::Owned(sdf.to_string())
-    //     }
-    // }
+    /// Set how compound names are matched by PubChem.
+    ///
+    /// Only used for compounds created with [`Compound::with_name`].
+    ///
+    /// # Example
+    /// ```
+    /// use pubchem::NameType;
+    ///
+    /// let compound = pubchem::Compound::with_name("acetylsalicylic").name_type(NameType::Word);
+    /// assert!(compound.cids().unwrap().contains(&2244));
+    /// ```
+    pub fn name_type(mut self, name_type: NameType) -> Self {
+        self.name_type = Some(name_type);
+        self
+    }
+
+    /// Set which compounds related to the input are returned by [`Compound::cids`].
+    ///
+    /// # Example
+    /// ```
+    /// use pubchem::CidsType;
+    ///
+    /// // Retrieve the parent compound of sodium acetylsalicylate
+    /// let compound = pubchem::Compound::new(517074).cids_type(CidsType::Parent);
+    /// assert_eq!(compound.cids().unwrap(), vec![2244]);
+    /// ```
+    pub fn cids_type(mut self, cids_type: CidsType) -> Self {
+        self.cids_type = Some(cids_type);
+        self
+    }
+
+    /// Set how XML elements unknown to the parsers are handled.
+    ///
+    /// Use [`ParseMode::Strict`] to detect additions to the PubChem schema,
+    /// for instance in tests.
+    pub fn parse_mode(mut self, parse_mode: ParseMode) -> Self {
+        self.parse_mode = parse_mode;
+        self
+    }
+
+    /// Create a new `Compound` cross-referenced by the given external identifier.
+    ///
+    /// # Example
+    /// ```
+    /// use pubchem::XrefType;
+    ///
+    /// let compound = pubchem::Compound::with_xref(XrefType::RN, "50-78-2");
+    /// assert!(compound.cids().unwrap().contains(&2244));
+    /// ```
+    pub fn with_xref(xref: XrefType, identifier: &str) -> Self {
+        Self::with_namespace(format!("xref/{}", xref.name()), identifier.to_string())
+    }
 
     /// Request the REST API for the given operation.
     ///
@@ -171,27 +609,44 @@ impl Compound {
     /// the appropriate method.
     ///
     fn request(&self, operation: &str) -> Result<ureq::Response, Error> {
-        let url = format!(
-            "https://pubchem.ncbi.nlm.nih.gov/rest/pug/{dom}/{ns}/{op}/XML",
+        self.request_with_query(operation, &[])
+    }
+
+    /// Send a request for the compound with additional query parameters.
+    fn request_with_query(
+        &self,
+        operation: &str,
+        query: &[(&str, &str)],
+    ) -> Result<ureq::Response, Error> {
+        post(
+            &self.url(operation, query),
+            &self.namespace,
+            &self.identifier,
+        )
+    }
+
+    /// Build the URL of a request for the compound, without the identifier.
+    fn url(&self, operation: &str, query: &[(&str, &str)]) -> String {
+        let mut url = format!(
+            "{base}/{dom}/{ns}/{op}/XML",
+            base = PUG_REST,
             dom = "compound",
             ns = &self.namespace,
             op = operation
         );
-        let form_data = form_urlencoded::Serializer::new(String::new())
-            .append_pair(&self.namespace, &self.identifier)
-            .finish();
-        match ureq::post(&url)
-            .set("Accept", "application/xml")
-            .set("Content-Type", "application/x-www-form-urlencoded")
-            .send_string(&form_data)
-        {
-            Err(ureq::Error::Status(400 | 404 | 405 | 500 | 501 | 503 | 504, response)) => {
-                let fault = rest::Fault::from_api_response(response)?;
-                Err(Error::Api(fault.into()))
-            }
-            Err(e) => Err(Error::Request(e)),
-            Ok(response) => Ok(response),
+        let mut query = query.to_vec();
+        if let Some(name_type) = self.name_type.filter(|_| self.namespace == "name") {
+            query.push(("name_type", name_type.name()));
+        }
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(
+                &form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(query)
+                    .finish(),
+            );
         }
+        url
     }
 
     /// Retrieve several properties at once for the compound.
@@ -202,16 +657,16 @@ impl Compound {
         let mut path = String::from("/property/");
         path.push_str(&properties.into_iter().map(CompoundProperty::name).join(","));
         self.request(&path)
-            .and_then(|response| rest::PropertyTable::from_api_response(response))
-            .map(|mut table| table.properties.pop().unwrap())
+            .and_then(|r| rest::PropertyTable::from_api_response_with(r, self.parse_mode))
+            .and_then(|mut table| table.properties.pop().ok_or(Error::EmptyResponse))
     }
 
     /// Retrieve the main PubChem designation for the compound.
     pub fn title(&self) -> Result<String, Error> {
         let properties = self.properties(&[CompoundProperty::Title])?;
-        Ok(properties
+        properties
             .title
-            .expect("All PubChem compounds should have a title."))
+            .ok_or(Error::MissingProperty(CompoundProperty::Title))
     }
 
     /// Retrieve the molecular formula of the compound.
@@ -223,17 +678,17 @@ impl Compound {
     /// ```
     pub fn molecular_formula(&self) -> Result<String, Error> {
         let properties = self.properties(&[CompoundProperty::MolecularFormula])?;
-        Ok(properties
+        properties
             .molecular_formula
-            .expect("All PubChem compounds should have a formula."))
+            .ok_or(Error::MissingProperty(CompoundProperty::MolecularFormula))
     }
 
     /// Retrieve the canonical SMILES string for the compound.
     pub fn canonical_smiles(&self) -> Result<String, Error> {
         let properties = self.properties(&[CompoundProperty::CanonicalSMILES])?;
-        Ok(properties
+        properties
             .canonical_smiles
-            .expect("All PubChem compounds should have a SMILES."))
+            .ok_or(Error::MissingProperty(CompoundProperty::CanonicalSMILES))
     }
 
     /// Retrieve the isomeric SMILES string for the compound.
@@ -245,9 +700,9 @@ impl Compound {
     /// ```
     pub fn isomeric_smiles(&self) -> Result<String, Error> {
         let properties = self.properties(&[CompoundProperty::IsomericSMILES])?;
-        Ok(properties
+        properties
             .isomeric_smiles
-            .expect("All PubChem compounds should have a SMILES."))
+            .ok_or(Error::MissingProperty(CompoundProperty::IsomericSMILES))
     }
 
     // /// Retrieve the entire PubChem record for the compound.
@@ -258,115 +713,1614 @@ impl Compound {
     /// Retrieve synonym names for the compound.
     pub fn synonyms(&self) -> Result<Vec<String>, Error> {
         self.request("synonyms")
-            .and_then(|response| rest::InformationList::from_api_response(response))
-            .map(|mut list| list.informations.pop().unwrap().synonyms)
+            .and_then(|r| rest::InformationList::from_api_response_with(r, self.parse_mode))
+            .and_then(|mut list| list.informations.pop().ok_or(Error::EmptyResponse))
+            .map(|information| information.synonyms)
     }
 
     /// Retrieve the Compound IDs designating the compound.
+    ///
+    /// Use [`Compound::cids_type`] to retrieve the Compound IDs of related
+    /// compounds instead.
     pub fn cids(&self) -> Result<Vec<i32>, Error> {
-        self.request("cids")
-            .and_then(|response| rest::IdentifierList::from_api_response(response))
+        let query = self
+            .cids_type
+            .map(|ty| vec![("cids_type", ty.name())])
+            .unwrap_or_default();
+        self.request_with_query("cids", &query)
+            .and_then(|r| rest::IdentifierList::from_api_response_with(r, self.parse_mode))
+            .map(|list| list.cids)
+    }
+
+    /// Retrieve the Compound IDs of the parent compounds of the compound.
+    ///
+    /// The parent of a compound is its important component, e.g. the
+    /// neutralized form of a salt, or the compound itself when it is not a
+    /// mixture.
+    ///
+    /// # Example
+    /// ```
+    /// // sodium acetate
+    /// let compound = pubchem::Compound::new(517045);
+    /// assert_eq!(compound.parent().unwrap(), vec![176]);
+    /// ```
+    pub fn parent(&self) -> Result<Vec<i32>, Error> {
+        self.related_cids(CidsType::Parent)
+    }
+
+    /// Retrieve the Compound IDs of the individual components of the compound.
+    pub fn components(&self) -> Result<Vec<i32>, Error> {
+        self.related_cids(CidsType::Component)
+    }
+
+    /// Retrieve the Compound IDs of the compounds with the same connectivity.
+    pub fn same_connectivity(&self) -> Result<Vec<i32>, Error> {
+        self.related_cids(CidsType::SameConnectivity)
+    }
+
+    /// Retrieve the Compound IDs of the compounds with the same stereochemistry.
+    pub fn same_stereo(&self) -> Result<Vec<i32>, Error> {
+        self.related_cids(CidsType::SameStereo)
+    }
+
+    /// Retrieve the Compound IDs of the compounds with the same isotopes.
+    pub fn same_isotopes(&self) -> Result<Vec<i32>, Error> {
+        self.related_cids(CidsType::SameIsotopes)
+    }
+
+    /// Retrieve the Compound IDs of compounds related to the compound.
+    fn related_cids(&self, cids_type: CidsType) -> Result<Vec<i32>, Error> {
+        self.request_with_query("cids", &[("cids_type", cids_type.name())])
+            .and_then(|r| rest::IdentifierList::from_api_response_with(r, self.parse_mode))
             .map(|list| list.cids)
     }
 
     /// Retrieve the Substance IDs associated with the compound.
     pub fn sids(&self) -> Result<Vec<i32>, Error> {
         self.request("sids")
-            .and_then(|response| rest::InformationList::from_api_response(response))
-            .map(|mut list| list.informations.pop().unwrap().sids)
+            .and_then(|r| rest::InformationList::from_api_response_with(r, self.parse_mode))
+            .and_then(|mut list| list.informations.pop().ok_or(Error::EmptyResponse))
+            .map(|information| information.sids)
     }
 
     /// Retrieve the Assay IDs associated with the compound.
     pub fn aids(&self) -> Result<Vec<i32>, Error> {
         self.request("aids")
-            .and_then(|response| rest::InformationList::from_api_response(response))
-            .map(|mut list| list.informations.pop().unwrap().aids)
+            .and_then(|r| rest::InformationList::from_api_response_with(r, self.parse_mode))
+            .and_then(|mut list| list.informations.pop().ok_or(Error::EmptyResponse))
+            .map(|information| information.aids)
+    }
+
+    /// Retrieve cross-references of the given types for the compound.
+    ///
+    /// The returned [`Information`](rest::Information) only has the fields
+    /// corresponding to the requested cross-references populated.
+    pub fn xrefs<'x, X>(&self, xrefs: X) -> Result<rest::Information, Error>
+    where
+        X: IntoIterator<Item = &'x XrefType>,
+    {
+        let mut path = String::from("xrefs/");
+        path.push_str(&xrefs.into_iter().map(XrefType::name).join(","));
+        self.request(&path)
+            .and_then(|r| rest::InformationList::from_api_response_with(r, self.parse_mode))
+            .and_then(|mut list| list.informations.pop().ok_or(Error::EmptyResponse))
+    }
+
+    /// Retrieve the textual descriptions of the compound.
+    ///
+    /// # Example
+    /// ```
+    /// let compound = pubchem::Compound::new(2244);
+    /// for description in compound.description().unwrap() {
+    ///     println!("{}", description.text);
+    /// }
+    /// ```
+    pub fn description(&self) -> Result<Vec<Description>, Error> {
+        self.request("description")
+            .and_then(|r| rest::InformationList::from_api_response_with(r, self.parse_mode))
+            .map(|list| {
+                Description::from_information_list(list)
+                    .into_iter()
+                    .flat_map(|(_, descriptions)| descriptions)
+                    .collect()
+            })
+    }
+
+    /// Retrieve the classification hierarchies the compound belongs to.
+    ///
+    /// # Example
+    /// ```
+    /// let compound = pubchem::Compound::new(2244);
+    /// let hierarchies = compound.classification().unwrap();
+    /// assert!(hierarchies.iter().any(|h| h.source_name == "MeSH"));
+    /// ```
+    pub fn classification(&self) -> Result<rest::Hierarchies, Error> {
+        self.request("classification")
+            .and_then(|r| rest::Hierarchies::from_api_response_with(r, self.parse_mode))
+    }
+
+    /// Retrieve a date associated with the compound.
+    ///
+    /// Returns `None` if PubChem does not record a date of this type for
+    /// the compound.
+    ///
+    /// # Example
+    /// ```
+    /// use pubchem::DateType;
+    ///
+    /// let compound = pubchem::Compound::new(2244);
+    /// let created = compound.dates(DateType::Creation).unwrap().unwrap();
+    /// assert_eq!(created.to_string(), "2005-03-26");
+    /// ```
+    pub fn dates(&self, date_type: DateType) -> Result<Option<rest::DateTime>, Error> {
+        let list = self
+            .request_with_query("dates", &[("dates_type", date_type.name())])
+            .and_then(|r| rest::InformationList::from_api_response_with(r, self.parse_mode))?;
+        Ok(list
+            .informations
+            .into_iter()
+            .next()
+            .and_then(|i| match date_type {
+                DateType::Deposition => i.deposition_date,
+                DateType::Modification => i.modification_date,
+                DateType::Hold => i.hold_date,
+                DateType::Creation => i.creation_date,
+            }))
+    }
+
+    /// Retrieve the Compound ID of the compound, resolving it if needed.
+    fn resolve_cid(&self) -> Result<i32, Error> {
+        if self.namespace == "cid" {
+            return Ok(self.identifier.parse()?);
+        }
+        let cids = self.cids()?;
+        cids.first().cloned().ok_or_else(|| {
+            let message = format!("no compound found for {}", self.identifier);
+            Error::Api(ApiError::NotFound(message.into()))
+        })
+    }
+
+    /// Get a `Compound` for the resolved CID, with the same parse mode.
+    fn resolved(&self) -> Result<Compound, Error> {
+        let cid = self.resolve_cid()? as u32;
+        Ok(Compound::new(cid).parse_mode(self.parse_mode))
+    }
+
+    /// Query the PUG-View API for the summary page of the compound.
+    fn view_request(&self, heading: Option<&str>) -> Result<view::Record, Error> {
+        let mut url = format!(
+            "{base}/data/compound/{cid}/XML",
+            base = PUG_VIEW,
+            cid = self.resolve_cid()?,
+        );
+        if let Some(heading) = heading {
+            url.push('?');
+            url.push_str(
+                &form_urlencoded::Serializer::new(String::new())
+                    .append_pair("heading", heading)
+                    .finish(),
+            );
+        }
+        get(&url).and_then(|r| view::Record::from_api_response_with(r, self.parse_mode))
+    }
+
+    /// Retrieve the full PUG-View record of the compound summary page.
+    ///
+    /// *Records of popular compounds can be several megabytes large, consider
+    /// using [`Compound::view_heading`] to only retrieve a single section.*
+    pub fn view(&self) -> Result<view::Record, Error> {
+        self.view_request(None)
+    }
+
+    /// Retrieve the PUG-View record of the compound restricted to a heading.
+    ///
+    /// # Example
+    /// ```
+    /// let compound = pubchem::Compound::new(2244);
+    /// let record = compound.view_heading("Melting Point").unwrap();
+    /// let section = record.section("Melting Point").unwrap();
+    /// for information in section.all_information() {
+    ///     println!("{}", information.value);
+    /// }
+    /// ```
+    pub fn view_heading(&self, heading: &str) -> Result<view::Record, Error> {
+        self.view_request(Some(heading))
+    }
+
+    /// Retrieve the GHS hazard classification of the compound.
+    ///
+    /// Compounds without any GHS classification in PubChem get an empty
+    /// classification rather than an error.
+    ///
+    /// # Example
+    /// ```
+    /// use pubchem::ghs::HazardCode;
+    ///
+    /// let compound = pubchem::Compound::with_name("aspirin");
+    /// let ghs = compound.ghs_classification().unwrap();
+    /// assert!(ghs.hazard_codes().contains(&HazardCode::H302));
+    /// ```
+    pub fn ghs_classification(&self) -> Result<ghs::GhsClassification, Error> {
+        // resolve the CID first so that a missing section is not mistaken
+        // for a missing compound
+        let compound = self.resolved()?;
+        match compound.view_heading(ghs::HEADING) {
+            Ok(record) => Ok(ghs::GhsClassification::from_record(&record)),
+            Err(Error::Api(ApiError::NotFound(_))) => Ok(ghs::GhsClassification::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Retrieve experimental values of the given properties for the compound.
+    ///
+    /// All the properties are extracted from a single PUG-View request.
+    /// Compounds without any experimental property in PubChem get an empty
+    /// list rather than an error.
+    ///
+    /// # Example
+    /// ```
+    /// use pubchem::experimental::ExperimentalProperty::*;
+    ///
+    /// let compound = pubchem::Compound::with_name("acetone");
+    /// for value in compound.experimental_properties(&[MeltingPoint, BoilingPoint]).unwrap() {
+    ///     if let Some(measurement) = value.measurement {
+    ///         println!("{}: {:.1} °C", value.property.heading(), measurement.value());
+    ///     }
+    /// }
+    /// ```
+    pub fn experimental_properties<'p, P>(
+        &self,
+        properties: P,
+    ) -> Result<Vec<experimental::ExperimentalValue>, Error>
+    where
+        P: IntoIterator<Item = &'p experimental::ExperimentalProperty>,
+    {
+        let compound = self.resolved()?;
+        let record = match compound.view_heading(experimental::HEADING) {
+            Ok(record) => record,
+            Err(Error::Api(ApiError::NotFound(_))) => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        Ok(properties
+            .into_iter()
+            .flat_map(|&p| experimental::ExperimentalValue::extract(&record, p))
+            .collect())
     }
 
     // pub fn assay_summary(&self) {
     //
     // }
     //
-    // pub fn classification(&self) {
-    //
-    // }
-    //
-    // pub fn xref(&self) {
-    //
-    // }
-    //
-    // pub fn description(&self) {
-    //
-    // }
-    //
     // pub fn conformers(&self) {
     //
     // }
 }
 
+/// The default number of identifiers sent in a single request by [`Compounds`].
+pub const DEFAULT_CHUNK_SIZE: usize = 500;
+
+/// The maximum number of requests per second allowed by PubChem.
+pub const MAX_REQUESTS_PER_SECOND: u32 = 5;
+
+#[derive(Debug)]
+/// A failed request for a chunk of identifiers in a batch.
+pub struct ChunkError {
+    /// The identifiers sent in the failed request.
+    pub identifiers: Vec<String>,
+    /// The error returned for the request.
+    pub error: Error,
+}
+
 #[derive(Debug)]
+/// The merged results of a batch of requests, with the failed chunks.
+pub struct Batch<T> {
+    /// The merged results of the successful requests.
+    pub result: T,
+    /// The input identifiers with a record in the results, in input order.
+    pub found: Vec<String>,
+    /// The input identifiers of successful requests without any record in
+    /// the results, in input order.
+    pub missing: Vec<String>,
+    /// The chunks for which the request failed, in input order.
+    pub failures: Vec<ChunkError>,
+}
+
+impl<T> Batch<T> {
+    /// Check whether all the requests of the batch succeeded.
+    ///
+    /// A complete batch may still have [`missing`](Batch::missing)
+    /// identifiers, which PubChem silently dropped from the results.
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Get the merged results, or the error of the first failed chunk.
+    pub fn into_result(self) -> Result<T, Error> {
+        match self.failures.into_iter().next() {
+            Some(failure) => Err(failure.error),
+            None => Ok(self.result),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The Compound IDs matching a single input identifier.
+pub enum Resolved {
+    /// The identifier matched one or more compounds.
+    Found(Vec<i32>),
+    /// The identifier did not match any compound.
+    NotFound,
+}
+
+impl Resolved {
+    /// Create a new `Resolved` from the Compound IDs returned by PubChem.
+    ///
+    /// PubChem returns a CID of 0 for valid structures it does not know of,
+    /// so these are ignored.
+    fn from_cids(mut cids: Vec<i32>) -> Self {
+        cids.retain(|&cid| cid > 0);
+        if cids.is_empty() {
+            Resolved::NotFound
+        } else {
+            Resolved::Found(cids)
+        }
+    }
+
+    /// Check whether the identifier matched at least one compound.
+    pub fn is_found(&self) -> bool {
+        matches!(self, Resolved::Found(_))
+    }
+
+    /// Get the Compound IDs matching the identifier.
+    pub fn cids(&self) -> &[i32] {
+        match self {
+            Resolved::Found(cids) => cids.as_slice(),
+            Resolved::NotFound => &[],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 /// A client for retrieving information about multiple PubChem compoumds at once.
+///
+/// Identifiers are sent in chunks of [`DEFAULT_CHUNK_SIZE`] identifiers,
+/// to avoid PubChem timing out or rejecting requests for large queries.
+/// Results of the different chunks are merged in input order.
+///
+/// # Example
+/// ```no_run
+/// use pubchem::CompoundProperty;
+///
+/// let compounds = pubchem::Compounds::new(1..=10_000)
+///     .chunk_size(1000)
+///     .concurrency(3);
+/// let batch = compounds.properties_batch(&[CompoundProperty::Title]);
+/// for failure in &batch.failures {
+///     eprintln!("failed to retrieve {} compounds: {}", failure.identifiers.len(), failure.error);
+/// }
+/// println!("retrieved {} compounds", batch.result.len());
+/// ```
 pub struct Compounds {
     namespace: Cow<'static, str>,
-    identifiers: Cow<'static, str>,
+    identifiers: Vec<String>,
+    chunk_size: usize,
+    concurrency: usize,
+    isolate_failures: bool,
+    parse_mode: ParseMode,
 }
 
 impl Compounds {
     /// Query several compounds with the given compound IDs.
     pub fn new<I: IntoIterator<Item = u32>>(ids: I) -> Self {
-        Self {
-            namespace: Cow::Borrowed("cid"),
-            identifiers: Cow::Owned(ids.into_iter().join(",")),
-        }
+        Self::with_namespace("cid", ids.into_iter().map(|id| id.to_string()))
     }
 
-    /// Request the REST API for the given operation.
+    /// Query several compounds matching the given compound names.
     ///
-    /// The response is checked to see if the HTTP client or the API errored,
-    /// otherwise the raw response is returned so that it can be parsed by
-    /// the appropriate method.
+    /// *PubChem only accepts a single name per request, so names are
+    /// always sent one by one.*
+    pub fn with_names<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::with_namespace("name", names.into_iter().map(|s| s.as_ref().to_string()))
+    }
+
+    /// Query several compounds matching the given SMILES.
     ///
-    fn request(&self, operation: &str) -> Result<ureq::Response, Error> {
-        let url = format!(
-            "https://pubchem.ncbi.nlm.nih.gov/rest/pug/{dom}/{ns}/{op}/XML",
-            dom = "compound",
+    /// *PubChem only accepts a single SMILES per request, so SMILES are
+    /// always sent one by one.*
+    pub fn with_smiles<I, S>(smiles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::with_namespace("smiles", smiles.into_iter().map(|s| s.as_ref().to_string()))
+    }
+
+    /// Query several compounds matching the given InChIs.
+    ///
+    /// *PubChem only accepts a single InChI per request, so InChIs are
+    /// always sent one by one.*
+    pub fn with_inchis<I, S>(inchis: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::with_namespace("inchi", inchis.into_iter().map(|s| s.as_ref().to_string()))
+    }
+
+    /// Query several compounds matching the given InChIKeys.
+    pub fn with_inchikeys<I, S>(inchikeys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::with_namespace(
+            "inchikey",
+            inchikeys.into_iter().map(|s| s.as_ref().to_string()),
+        )
+    }
+
+    fn with_namespace<I>(namespace: &'static str, identifiers: I) -> Self
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut compounds = Self {
+            namespace: Cow::Borrowed(namespace),
+            identifiers: identifiers.into_iter().collect(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            concurrency: 1,
+            isolate_failures: false,
+            parse_mode: ParseMode::Lenient,
+        };
+        compounds.chunk_size = compounds.max_chunk_size().min(DEFAULT_CHUNK_SIZE);
+        compounds
+    }
+
+    /// Get the maximum number of identifiers PubChem accepts in a single request.
+    fn max_chunk_size(&self) -> usize {
+        match self.namespace.as_ref() {
+            "name" | "smiles" | "inchi" => 1,
+            _ => usize::MAX,
+        }
+    }
+
+    /// Set the maximum number of identifiers sent in a single request.
+    ///
+    /// Names, SMILES and InChIs are always sent one by one, regardless of
+    /// the chunk size.
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.clamp(1, self.max_chunk_size());
+        self
+    }
+
+    /// Set the number of requests sent concurrently.
+    ///
+    /// Requests are always spaced to stay within the PubChem rate limit of
+    /// [`MAX_REQUESTS_PER_SECOND`], so there is no point in using more than
+    /// [`MAX_REQUESTS_PER_SECOND`] concurrent requests.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.clamp(1, MAX_REQUESTS_PER_SECOND as usize);
+        self
+    }
+
+    /// Retry failed chunks and missing identifiers one by one, or not.
+    ///
+    /// PubChem may reject a whole request because of a single identifier,
+    /// or silently drop the records of some identifiers. When enabled, the
+    /// identifiers of a failed chunk, as well as the identifiers missing
+    /// from the results of a chunk, are sent again in separate requests,
+    /// so that only the bad identifiers are reported as failed or missing.
+    /// Identifiers for which PubChem reports a [`ApiError::NotFound`] error
+    /// are then reported as missing rather than failed.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use pubchem::CompoundProperty;
+    ///
+    /// let compounds = pubchem::Compounds::new([2244, 999_999_999, 180]).isolate_failures(true);
+    /// let batch = compounds.properties_batch(&[CompoundProperty::Title]);
+    /// assert_eq!(batch.found, vec!["2244", "180"]);
+    /// assert_eq!(batch.missing, vec!["999999999"]);
+    /// ```
+    pub fn isolate_failures(mut self, isolate_failures: bool) -> Self {
+        self.isolate_failures = isolate_failures;
+        self
+    }
+
+    /// Set how XML elements unknown to the parsers are handled.
+    pub fn parse_mode(mut self, parse_mode: ParseMode) -> Self {
+        self.parse_mode = parse_mode;
+        self
+    }
+
+    /// Request the REST API for the given operation on a chunk of identifiers.
+    ///
+    /// The response is checked to see if the HTTP client or the API errored,
+    /// otherwise the raw response is returned so that it can be parsed by
+    /// the appropriate method.
+    ///
+    fn request(&self, operation: &str, identifiers: &[String]) -> Result<ureq::Response, Error> {
+        let url = format!(
+            "{base}/{dom}/{ns}/{op}/XML",
+            base = PUG_REST,
+            dom = "compound",
             ns = &self.namespace,
             op = operation
         );
-        let form_data = form_urlencoded::Serializer::new(String::new())
-            .append_pair(&self.namespace, &self.identifiers)
-            .finish();
-        match ureq::post(&url)
-            .set("Accept", "application/xml")
-            .set("Content-Type", "application/x-www-form-urlencoded")
-            .send_string(&form_data)
-        {
-            Err(ureq::Error::Status(400 | 404 | 405 | 500 | 501 | 503 | 504, response)) => {
-                let fault = rest::Fault::from_api_response(response)?;
-                Err(Error::Api(fault.into()))
+        post(&url, &self.namespace, &identifiers.join(","))
+    }
+
+    /// Run `f` on every chunk of identifiers, and merge the results in order.
+    ///
+    /// Every identifier of a successful chunk is reported as found.
+    fn batch<T, F>(&self, f: F) -> Batch<T>
+    where
+        T: Default + Extend<<T as IntoIterator>::Item> + IntoIterator + Send,
+        F: Fn(&[String]) -> Result<T, Error> + Sync,
+    {
+        self.batch_with_keys(f, |_| None)
+    }
+
+    /// Run `f` on every chunk of identifiers, and merge the results in order.
+    ///
+    /// `keys` returns the keys of the identifiers with a record in the
+    /// result of a chunk, as given by [`Compounds::key`], or `None` if the
+    /// records cannot be mapped back to the input identifiers.
+    fn batch_with_keys<T, F, K>(&self, f: F, keys: K) -> Batch<T>
+    where
+        T: Default + Extend<<T as IntoIterator>::Item> + IntoIterator + Send,
+        F: Fn(&[String]) -> Result<T, Error> + Sync,
+        K: Fn(&T) -> Option<BTreeSet<String>> + Sync,
+    {
+        let chunks = self.identifiers.chunks(self.chunk_size).collect::<Vec<_>>();
+        let limiter = utils::RateLimiter::new(MAX_REQUESTS_PER_SECOND);
+        let run = |chunk: &[String]| {
+            limiter.wait();
+            f(chunk)
+        };
+        let run_chunk = |chunk: &[String]| {
+            let mut batch = Batch {
+                result: T::default(),
+                found: Vec::new(),
+                missing: Vec::new(),
+                failures: Vec::new(),
+            };
+            let mut index = HashMap::new();
+            for (i, id) in chunk.iter().enumerate() {
+                index.entry(self.key(id)).or_insert(i);
+            }
+            let mut records = None;
+            let retry = match run(chunk) {
+                Ok(value) => {
+                    let returned = keys(&value);
+                    records = Some(value);
+                    for id in chunk {
+                        match &returned {
+                            Some(keys) if !keys.contains(&self.key(id)) => {
+                                batch.missing.push(id.clone())
+                            }
+                            _ => batch.found.push(id.clone()),
+                        }
+                    }
+                    if self.isolate_failures && chunk.len() > 1 {
+                        std::mem::take(&mut batch.missing)
+                    } else {
+                        Vec::new()
+                    }
+                }
+                Err(Error::Api(ApiError::NotFound(_)))
+                    if self.isolate_failures && chunk.len() == 1 =>
+                {
+                    batch.missing.push(chunk[0].clone());
+                    Vec::new()
+                }
+                Err(_) if self.isolate_failures && chunk.len() > 1 => chunk.to_vec(),
+                Err(error) => {
+                    batch.failures.push(ChunkError {
+                        identifiers: chunk.to_vec(),
+                        error,
+                    });
+                    Vec::new()
+                }
+            };
+            let mut retried = Vec::new();
+            for id in retry {
+                match run(std::slice::from_ref(&id)) {
+                    Ok(value) => {
+                        let found = keys(&value).is_none_or(|k| k.contains(&self.key(&id)));
+                        retried.push((index[&self.key(&id)], value));
+                        if found {
+                            batch.found.push(id);
+                        } else {
+                            batch.missing.push(id);
+                        }
+                    }
+                    Err(Error::Api(ApiError::NotFound(_))) => batch.missing.push(id),
+                    Err(error) => batch.failures.push(ChunkError {
+                        identifiers: vec![id],
+                        error,
+                    }),
+                }
+            }
+            // restore the input order of records and identifiers after retries
+            match records {
+                Some(records) if !retried.is_empty() => {
+                    // split the records of the chunk so that the records of
+                    // retried identifiers can be merged back at their position
+                    let mut position = 0;
+                    for record in records {
+                        let mut single = T::default();
+                        single.extend(std::iter::once(record));
+                        let keys = keys(&single).unwrap_or_default();
+                        if let Some(&i) = keys.iter().filter_map(|k| index.get(k)).min() {
+                            position = i;
+                        }
+                        retried.push((position, single));
+                    }
+                    retried.sort_by_key(|(i, _)| *i);
+                }
+                Some(records) => batch.result.extend(records),
+                None => (),
+            }
+            for (_, value) in retried {
+                batch.result.extend(value);
             }
-            Err(e) => Err(Error::Request(e)),
-            Ok(response) => Ok(response),
+            let position = |id: &String| index.get(&self.key(id)).copied();
+            batch.found.sort_by_key(position);
+            batch.missing.sort_by_key(position);
+            batch
+        };
+
+        let batches = if self.concurrency <= 1 || chunks.len() <= 1 {
+            chunks
+                .iter()
+                .map(|chunk| run_chunk(chunk))
+                .collect::<Vec<_>>()
+        } else {
+            let next = AtomicUsize::new(0);
+            let mut batches = std::thread::scope(|scope| {
+                let workers = (0..self.concurrency.min(chunks.len()))
+                    .map(|_| {
+                        scope.spawn(|| {
+                            let mut done = Vec::new();
+                            loop {
+                                let i = next.fetch_add(1, AtomicOrdering::Relaxed);
+                                match chunks.get(i) {
+                                    Some(chunk) => done.push((i, run_chunk(chunk))),
+                                    None => break done,
+                                }
+                            }
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                workers
+                    .into_iter()
+                    .flat_map(|worker| {
+                        worker
+                            .join()
+                            .unwrap_or_else(|e| std::panic::resume_unwind(e))
+                    })
+                    .collect::<Vec<_>>()
+            });
+            batches.sort_by_key(|(i, _)| *i);
+            batches.into_iter().map(|(_, batch)| batch).collect()
+        };
+
+        let mut merged = Batch {
+            result: T::default(),
+            found: Vec::new(),
+            missing: Vec::new(),
+            failures: Vec::new(),
+        };
+        for batch in batches {
+            merged.result.extend(batch.result);
+            merged.found.extend(batch.found);
+            merged.missing.extend(batch.missing);
+            merged.failures.extend(batch.failures);
+        }
+        merged
+    }
+
+    /// Get the key used to match an input identifier with the returned records.
+    fn key(&self, id: &str) -> String {
+        match self.namespace.as_ref() {
+            "inchikey" => id.trim().to_ascii_uppercase(),
+            _ => id.trim().to_string(),
+        }
+    }
+
+    /// Get the keys of the input identifiers with a record in a property table.
+    fn table_keys(&self, table: &rest::PropertyTable) -> Option<BTreeSet<String>> {
+        match self.namespace.as_ref() {
+            "cid" => Some(table.iter().map(|p| p.cid.to_string()).collect()),
+            "inchikey" => Some(
+                table
+                    .iter()
+                    .filter_map(|p| p.inchi_key.as_deref())
+                    .map(|key| self.key(key))
+                    .collect(),
+            ),
+            _ => None,
         }
     }
 
+    /// Retrieve the textual descriptions of the compounds.
+    ///
+    /// Descriptions are grouped by compound ID, in the order the compounds
+    /// were returned by PubChem.
+    pub fn description(&self) -> Result<Vec<(i32, Vec<Description>)>, Error> {
+        self.description_batch().into_result()
+    }
+
+    /// Retrieve the textual descriptions of the compounds, chunk by chunk.
+    pub fn description_batch(&self) -> Batch<Vec<(i32, Vec<Description>)>> {
+        self.batch_with_keys(
+            |chunk| {
+                self.request("description", chunk)
+                    .and_then(|r| rest::InformationList::from_api_response_with(r, self.parse_mode))
+                    .map(Description::from_information_list)
+            },
+            |descriptions| match self.namespace.as_ref() {
+                "cid" => Some(
+                    descriptions
+                        .iter()
+                        .map(|(cid, _)| cid.to_string())
+                        .collect(),
+                ),
+                _ => None,
+            },
+        )
+    }
+
     /// Retrieve several properties at once for the compounds.
+    ///
+    /// Fails with the error of the first failed chunk, use
+    /// [`Compounds::properties_batch`] to retrieve the properties of the
+    /// other chunks instead.
     pub fn properties<'p, P>(&self, properties: P) -> Result<rest::PropertyTable, Error>
     where
         P: IntoIterator<Item = &'p CompoundProperty>,
     {
+        self.properties_batch(properties).into_result()
+    }
+
+    /// Retrieve several properties at once for the compounds, chunk by chunk.
+    ///
+    /// *When querying compounds by InChIKey, the [`CompoundProperty::InChIKey`]
+    /// property is always retrieved, to map the records to the input
+    /// identifiers.*
+    pub fn properties_batch<'p, P>(&self, properties: P) -> Batch<rest::PropertyTable>
+    where
+        P: IntoIterator<Item = &'p CompoundProperty>,
+    {
+        let mut properties = properties.into_iter().collect::<Vec<_>>();
+        if self.namespace == "inchikey" && !properties.contains(&&CompoundProperty::InChIKey) {
+            properties.push(&CompoundProperty::InChIKey);
+        }
         let mut path = String::from("/property/");
         path.push_str(&properties.into_iter().map(CompoundProperty::name).join(","));
-        self.request(&path)
-            .and_then(|response| rest::PropertyTable::from_api_response(response))
+        self.batch_with_keys(
+            |chunk| {
+                self.request(&path, chunk)
+                    .and_then(|r| rest::PropertyTable::from_api_response_with(r, self.parse_mode))
+            },
+            |table| self.table_keys(table),
+        )
+    }
+
+    /// Retrieve the Compound IDs matching each input identifier.
+    ///
+    /// Entries are returned in input order, with identifiers that did not
+    /// match any compound marked as [`Resolved::NotFound`] rather than
+    /// failing the whole query.
+    ///
+    /// # Example
+    /// ```
+    /// let compounds = pubchem::Compounds::with_names(["aspirin", "not a compound"]);
+    /// let cids = compounds.cids().unwrap();
+    /// assert_eq!(cids[0].1.cids(), &[2244]);
+    /// assert!(!cids[1].1.is_found());
+    /// ```
+    pub fn cids(&self) -> Result<Vec<(String, Resolved)>, Error> {
+        self.batch(|chunk| self.resolve_chunk(chunk)).into_result()
+    }
+
+    /// Resolve each input identifier to its Compound IDs, and report the outcome.
+    ///
+    /// Unlike [`Compounds::cids`], failed requests do not fail the whole
+    /// query, but are reported with a [`ResolutionStatus::Error`] status
+    /// for every identifier of the failed chunk.
+    ///
+    /// # Example
+    /// ```no_run
+    /// let compounds = pubchem::Compounds::with_names(&["aspirin", "glucose", "water"]);
+    /// let report = compounds.resolve();
+    /// report.write_csv(std::io::stdout()).unwrap();
+    /// ```
+    ///
+    /// [`ResolutionStatus::Error`]: resolve::ResolutionStatus::Error
+    pub fn resolve(&self) -> resolve::ResolutionReport {
+        self.batch(|chunk| {
+            let resolutions = match self.resolve_chunk(chunk) {
+                Ok(resolved) => resolved
+                    .into_iter()
+                    .map(|(input, r)| match r {
+                        Resolved::Found(cids) => resolve::Resolution::new(input, cids),
+                        Resolved::NotFound => resolve::Resolution::new(input, Vec::new()),
+                    })
+                    .collect(),
+                Err(e) => chunk
+                    .iter()
+                    .map(|input| resolve::Resolution::error(input.clone(), e.to_string()))
+                    .collect(),
+            };
+            Ok(resolutions)
+        })
+        .result
+    }
+
+    /// Resolve the Compound IDs of a single chunk of identifiers.
+    fn resolve_chunk(&self, chunk: &[String]) -> Result<Vec<(String, Resolved)>, Error> {
+        if self.max_chunk_size() > 1 {
+            // retrieve the InChIKey property to map each CID to the input
+            // identifier, since PubChem does not return them in input order
+            let table = match self
+                .request("/property/InChIKey", chunk)
+                .and_then(|r| rest::PropertyTable::from_api_response_with(r, self.parse_mode))
+            {
+                Err(Error::Api(ApiError::NotFound(_))) => rest::PropertyTable::default(),
+                other => other?,
+            };
+            Ok(self.resolve_from_table(chunk, table))
+        } else {
+            chunk
+                .iter()
+                .map(|id| {
+                    let cids = match self
+                        .request("cids", std::slice::from_ref(id))
+                        .and_then(|r| {
+                            rest::IdentifierList::from_api_response_with(r, self.parse_mode)
+                        }) {
+                        Err(Error::Api(ApiError::NotFound(_))) => Vec::new(),
+                        other => other?.cids,
+                    };
+                    Ok((id.clone(), Resolved::from_cids(cids)))
+                })
+                .collect()
+        }
+    }
+
+    /// Map each input identifier to the Compound IDs of a property table.
+    fn resolve_from_table(
+        &self,
+        chunk: &[String],
+        table: rest::PropertyTable,
+    ) -> Vec<(String, Resolved)> {
+        let mut cids = BTreeMap::<String, Vec<i32>>::new();
+        for properties in table {
+            let key = match self.namespace.as_ref() {
+                "inchikey" => properties.inchi_key.unwrap_or_default(),
+                _ => properties.cid.to_string(),
+            };
+            cids.entry(key).or_default().push(properties.cid);
+        }
+        chunk
+            .iter()
+            .map(|id| {
+                let found = cids.get(&self.key(id)).cloned().unwrap_or_default();
+                (id.clone(), Resolved::from_cids(found))
+            })
+            .collect()
+    }
+
+    /// Iterate over the properties of the compounds as they are received.
+    ///
+    /// Unlike [`Compounds::properties`], properties are parsed one compound
+    /// at a time while the response is being read, so memory usage stays
+    /// bounded even for very large queries. Chunks are requested lazily, one
+    /// after the other; a failed chunk yields a single error, and the
+    /// iterator continues with the next chunk.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use pubchem::CompoundProperty;
+    ///
+    /// let compounds = pubchem::Compounds::new(1..=100_000);
+    /// for result in compounds.properties_iter(&[CompoundProperty::MolecularFormula]) {
+    ///     let properties = result.unwrap();
+    ///     println!("{}\t{:?}", properties.cid, properties.molecular_formula);
+    /// }
+    /// ```
+    pub fn properties_iter<'p, P>(&self, properties: P) -> PropertiesIter<'_>
+    where
+        P: IntoIterator<Item = &'p CompoundProperty>,
+    {
+        let mut path = String::from("/property/");
+        path.push_str(&properties.into_iter().map(CompoundProperty::name).join(","));
+        PropertiesIter {
+            compounds: self,
+            path,
+            chunks: self.identifiers.chunks(self.chunk_size),
+            stream: None,
+            limiter: utils::RateLimiter::new(MAX_REQUESTS_PER_SECOND),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// An iterator over the properties of several compounds.
+///
+/// Created by the [`Compounds::properties_iter`] method.
+pub struct PropertiesIter<'a> {
+    compounds: &'a Compounds,
+    path: String,
+    chunks: std::slice::Chunks<'a, String>,
+    stream: Option<ElementStream<rest::Properties>>,
+    limiter: utils::RateLimiter,
+}
+
+impl Iterator for PropertiesIter<'_> {
+    type Item = Result<rest::Properties, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(stream) = self.stream.as_mut() {
+                if let Some(item) = stream.next() {
+                    return Some(item);
+                }
+                self.stream = None;
+            }
+            let chunk = self.chunks.next()?;
+            self.limiter.wait();
+            let mode = self.compounds.parse_mode;
+            let stream = self
+                .compounds
+                .request(&self.path, chunk)
+                .and_then(|r| ElementStream::new(r, b"PropertyTable", b"Properties", mode));
+            match stream {
+                Ok(stream) => self.stream = Some(stream),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A client for retrieving the PUG-View annotations under a heading.
+///
+/// # Example
+/// ```
+/// let annotations = pubchem::Annotations::new("Boiling Point");
+/// let page = annotations.page(1).unwrap();
+/// for annotation in page.annotations {
+///     println!("{:?} {:?}", annotation.name, annotation.linked_records.cids);
+/// }
+/// ```
+pub struct Annotations {
+    heading: String,
+    heading_type: Option<String>,
+    parse_mode: ParseMode,
+}
+
+impl Annotations {
+    /// Create a new `Annotations` client for the given heading.
+    pub fn new(heading: &str) -> Self {
+        Self {
+            heading: heading.to_string(),
+            heading_type: None,
+            parse_mode: ParseMode::Lenient,
+        }
+    }
+
+    /// Restrict the annotations to the given record type (such as `Compound`).
+    ///
+    /// This is needed for headings used by several record types.
+    pub fn heading_type(mut self, heading_type: &str) -> Self {
+        self.heading_type = Some(heading_type.to_string());
+        self
+    }
+
+    /// Set how XML elements unknown to the parsers are handled.
+    pub fn parse_mode(mut self, parse_mode: ParseMode) -> Self {
+        self.parse_mode = parse_mode;
+        self
+    }
+
+    /// Retrieve a single page of annotations, starting from page 1.
+    pub fn page(&self, page: u32) -> Result<view::Annotations, Error> {
+        let mut query = form_urlencoded::Serializer::new(String::new());
+        query.append_pair("page", &page.to_string());
+        if let Some(heading_type) = &self.heading_type {
+            query.append_pair("heading_type", heading_type);
+        }
+        let heading = form_urlencoded::byte_serialize(self.heading.as_bytes()).join("");
+        let url = format!(
+            "{base}/annotations/heading/{heading}/XML?{query}",
+            base = PUG_VIEW,
+            heading = heading.replace('+', "%20"),
+            query = query.finish(),
+        );
+        get(&url).and_then(|r| view::Annotations::from_api_response_with(r, self.parse_mode))
+    }
+
+    /// Iterate over the annotations of every page under the heading.
+    ///
+    /// Pages are only requested when the annotations of the previous page
+    /// have all been consumed. The iterator stops after the first error.
+    ///
+    /// # Example
+    /// ```
+    /// let annotations = pubchem::Annotations::new("Boiling Point");
+    /// for annotation in annotations.iter().take(5) {
+    ///     let annotation = annotation.unwrap();
+    ///     println!("{:?}", annotation.linked_records.cids);
+    /// }
+    /// ```
+    pub fn iter(&self) -> AnnotationsIter<'_> {
+        AnnotationsIter {
+            client: self,
+            next_page: 1,
+            total_pages: None,
+            annotations: Vec::new().into_iter(),
+            done: false,
+        }
+    }
+
+    /// Retrieve all the available annotation headings, grouped by record type.
+    pub fn headings() -> Result<BTreeMap<String, Vec<String>>, Error> {
+        Self::headings_with(ParseMode::Lenient)
+    }
+
+    /// Retrieve all the available annotation headings, with the given parse mode.
+    pub fn headings_with(parse_mode: ParseMode) -> Result<BTreeMap<String, Vec<String>>, Error> {
+        let url = format!("{base}/annotations/headings/XML", base = PUG_REST);
+        let list =
+            get(&url).and_then(|r| rest::InformationList::from_api_response_with(r, parse_mode))?;
+        let mut headings = BTreeMap::<String, Vec<String>>::new();
+        for annotation in list.annotations {
+            headings
+                .entry(annotation.ty)
+                .or_default()
+                .push(annotation.heading);
+        }
+        Ok(headings)
+    }
+}
+
+#[derive(Debug)]
+/// An iterator over the annotations of all pages under a heading.
+///
+/// Created by the [`Annotations::iter`] method.
+pub struct AnnotationsIter<'a> {
+    client: &'a Annotations,
+    next_page: u32,
+    total_pages: Option<u32>,
+    annotations: std::vec::IntoIter<view::Annotation>,
+    done: bool,
+}
+
+impl AnnotationsIter<'_> {
+    /// Get the total number of pages, if the first page was retrieved.
+    pub fn total_pages(&self) -> Option<u32> {
+        self.total_pages
+    }
+}
+
+impl Iterator for AnnotationsIter<'_> {
+    type Item = Result<view::Annotation, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(annotation) = self.annotations.next() {
+                return Some(Ok(annotation));
+            }
+            if self.done || self.total_pages.is_some_and(|t| self.next_page > t) {
+                return None;
+            }
+            match self.client.page(self.next_page) {
+                Ok(page) => {
+                    self.next_page += 1;
+                    self.total_pages = Some(page.total_pages.max(0) as u32);
+                    self.annotations = page.annotations.into_iter();
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A client for retrieving the records under a classification node.
+///
+/// Classification nodes are identified by their HNID, which can be obtained
+/// from the [`NodeInformation`](rest::NodeInformation) of the hierarchies
+/// returned by [`Compound::classification`].
+///
+/// # Example
+/// ```
+/// // Retrieve all compounds classified as "Amino Acids" by ChEBI
+/// let node = pubchem::ClassificationNode::new(1856916);
+/// let cids = node.cids().unwrap();
+/// ```
+pub struct ClassificationNode {
+    hnid: u32,
+    parse_mode: ParseMode,
+}
+
+impl ClassificationNode {
+    /// Create a new `ClassificationNode` with the given node ID.
+    pub fn new(hnid: u32) -> Self {
+        Self {
+            hnid,
+            parse_mode: ParseMode::Lenient,
+        }
+    }
+
+    /// Set how XML elements unknown to the parsers are handled.
+    pub fn parse_mode(mut self, parse_mode: ParseMode) -> Self {
+        self.parse_mode = parse_mode;
+        self
+    }
+
+    /// Request the REST API for the given identifier type.
+    fn request(&self, id_type: &str) -> Result<rest::IdentifierList, Error> {
+        let url = format!(
+            "{base}/classification/hnid/{hnid}/{ty}/XML",
+            base = PUG_REST,
+            hnid = self.hnid,
+            ty = id_type,
+        );
+        get(&url).and_then(|r| rest::IdentifierList::from_api_response_with(r, self.parse_mode))
+    }
+
+    /// Retrieve the Compound IDs of all the compounds under the node.
+    pub fn cids(&self) -> Result<Vec<i32>, Error> {
+        self.request("cids").map(|list| list.cids)
+    }
+
+    /// Retrieve the Substance IDs of all the substances under the node.
+    pub fn sids(&self) -> Result<Vec<i32>, Error> {
+        self.request("sids").map(|list| list.sids)
+    }
+
+    /// Retrieve the Assay IDs of all the assays under the node.
+    pub fn aids(&self) -> Result<Vec<i32>, Error> {
+        self.request("aids").map(|list| list.aids)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The kind of structure search to perform.
+pub enum StructureSearchType {
+    /// Find compounds containing the query structure.
+    Substructure,
+    /// Find compounds contained in the query structure.
+    Superstructure,
+    /// Find compounds with a 2D fingerprint similar to the query structure.
+    Similarity,
+    /// Find compounds identical to the query structure.
+    Identity,
+}
+
+impl StructureSearchType {
+    /// Get the name of the PubChem operation for this search type.
+    fn operation(&self, fast: bool) -> &'static str {
+        match (self, fast) {
+            (StructureSearchType::Substructure, true) => "fastsubstructure",
+            (StructureSearchType::Substructure, false) => "substructure",
+            (StructureSearchType::Superstructure, true) => "fastsuperstructure",
+            (StructureSearchType::Superstructure, false) => "superstructure",
+            (StructureSearchType::Similarity, true) => "fastsimilarity_2d",
+            (StructureSearchType::Similarity, false) => "similarity",
+            (StructureSearchType::Identity, true) => "fastidentity",
+            (StructureSearchType::Identity, false) => "identity",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The handling of stereochemistry in substructure and superstructure searches.
+pub enum StereoMode {
+    /// Ignore stereochemistry entirely.
+    Ignore,
+    /// Require an exact match of the stereocenters.
+    Exact,
+    /// Require a match of the relative configuration of the stereocenters.
+    Relative,
+    /// Allow any stereocenters that do not conflict with the query.
+    NonConflicting,
+}
+
+impl StereoMode {
+    /// Get the value of the `Stereo` parameter for this mode.
+    fn name(&self) -> &'static str {
+        match self {
+            StereoMode::Ignore => "ignore",
+            StereoMode::Exact => "exact",
+            StereoMode::Relative => "relative",
+            StereoMode::NonConflicting => "nonconflicting",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The level of identity required for hits of an identity search.
+pub enum IdentityType {
+    /// Require the same connectivity, ignoring stereochemistry and isotopes.
+    SameConnectivity,
+    /// Require the same tautomer, ignoring stereochemistry and isotopes.
+    SameTautomer,
+    /// Require the same stereochemistry.
+    SameStereo,
+    /// Require the same isotopes.
+    SameIsotope,
+    /// Require the same stereochemistry and isotopes.
+    SameStereoIsotope,
+    /// Allow any stereochemistry that does not conflict with the query.
+    NonConflictingStereo,
+    /// Require the same isotopes, and non-conflicting stereochemistry.
+    SameIsotopeNonConflictingStereo,
+}
+
+impl IdentityType {
+    /// Get the value of the `identity_type` parameter for this level.
+    fn name(&self) -> &'static str {
+        match self {
+            IdentityType::SameConnectivity => "same_connectivity",
+            IdentityType::SameTautomer => "same_tautomer",
+            IdentityType::SameStereo => "same_stereo",
+            IdentityType::SameIsotope => "same_isotope",
+            IdentityType::SameStereoIsotope => "same_stereo_isotope",
+            IdentityType::NonConflictingStereo => "nonconflicting_stereo",
+            IdentityType::SameIsotopeNonConflictingStereo => "same_isotope_nonconflicting_stereo",
+        }
+    }
+}
+
+/// A response to a structure search, either final or pending.
+enum SearchResponse {
+    Waiting(rest::Waiting),
+    Identifiers(rest::IdentifierList),
+}
+
+impl FromXml for SearchResponse {
+    fn from_xml<B: std::io::BufRead>(
+        event: &quick_xml::events::BytesStart,
+        reader: &mut quick_xml::Reader<B>,
+        buffer: &mut Vec<u8>,
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        match event.local_name() {
+            b"Waiting" => rest::Waiting::from_xml(event, reader, buffer, mode).map(Self::Waiting),
+            _ => rest::IdentifierList::from_xml(event, reader, buffer, mode).map(Self::Identifiers),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A client for searching PubChem compounds by structure.
+///
+/// By default, the *fast* variants of the searches are used, which return
+/// their results synchronously. The other variants are asynchronous on the
+/// PubChem side: the client will poll the server until the results are
+/// available.
+///
+/// # Example
+/// ```
+/// use pubchem::StructureSearchType::Substructure;
+///
+/// let cids = pubchem::StructureSearch::with_smiles(Substructure, "C1=CC=C2C(=C1)C=CC=N2")
+///     .max_records(10)
+///     .cids()
+///     .unwrap();
+/// assert_eq!(cids.len(), 10);
+/// ```
+pub struct StructureSearch {
+    search_type: StructureSearchType,
+    namespace: Cow<'static, str>,
+    identifier: Cow<'static, str>,
+    fast: bool,
+    poll_interval: Duration,
+    max_polls: u32,
+    parse_mode: ParseMode,
+    options: Vec<(&'static str, String)>,
+}
+
+impl StructureSearch {
+    /// Search compounds using the structure of the given compound ID.
+    pub fn new(search_type: StructureSearchType, id: u32) -> Self {
+        Self::with_namespace(search_type, "cid", id.to_string())
+    }
+
+    /// Search compounds using the given SMILES as a query.
+    pub fn with_smiles(search_type: StructureSearchType, smiles: &str) -> Self {
+        Self::with_namespace(search_type, "smiles", smiles.to_string())
+    }
+
+    /// Search compounds using the given InChI as a query.
+    pub fn with_inchi(search_type: StructureSearchType, inchi: &str) -> Self {
+        Self::with_namespace(search_type, "inchi", inchi.to_string())
+    }
+
+    /// Search compounds using the given SDF record as a query.
+    ///
+    /// The MOL block of the record is checked locally, see
+    /// [`Compound::with_sdf`] for more details.
+    pub fn with_sdf(search_type: StructureSearchType, sdf: &str) -> Result<Self, Error> {
+        utils::validate_sdf(sdf)?;
+        Ok(Self::with_namespace(search_type, "sdf", sdf.to_string()))
+    }
+
+    fn with_namespace(
+        search_type: StructureSearchType,
+        namespace: &'static str,
+        identifier: String,
+    ) -> Self {
+        Self {
+            search_type,
+            namespace: Cow::Borrowed(namespace),
+            identifier: Cow::Owned(identifier),
+            fast: true,
+            poll_interval: Duration::from_secs(2),
+            max_polls: 150,
+            parse_mode: ParseMode::Lenient,
+            options: Vec::new(),
+        }
+    }
+
+    /// Set an option of the search, replacing any previous value.
+    fn option<V: ToString>(mut self, key: &'static str, value: V) -> Self {
+        self.options.retain(|(k, _)| *k != key);
+        self.options.push((key, value.to_string()));
+        self
+    }
+
+    /// Use the fast (synchronous) variant of the search, or not.
+    pub fn fast(mut self, fast: bool) -> Self {
+        self.fast = fast;
+        self
+    }
+
+    /// Set the delay between two queries while waiting for the search results.
+    ///
+    /// *Only used for the non-fast variants of the search.*
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Set the maximum number of queries while waiting for the search results.
+    ///
+    /// An [`ApiError::Timeout`] error mentioning the ListKey of the search
+    /// is returned if the results are still not ready after that many
+    /// queries. Defaults to 150, or 5 minutes with the default interval.
+    ///
+    /// *Only used for the non-fast variants of the search.*
+    pub fn max_polls(mut self, max_polls: u32) -> Self {
+        self.max_polls = max_polls;
+        self
+    }
+
+    /// Set how XML elements unknown to the parsers are handled.
+    pub fn parse_mode(mut self, parse_mode: ParseMode) -> Self {
+        self.parse_mode = parse_mode;
+        self
+    }
+
+    /// Set the minimum Tanimoto score (in percent) for a similarity search.
+    ///
+    /// Scores above 100 are clamped to 100.
+    pub fn threshold(self, threshold: u8) -> Self {
+        self.option("Threshold", threshold.min(100))
+    }
+
+    /// Require isotopes to match the query.
+    pub fn match_isotopes(self, value: bool) -> Self {
+        self.option("MatchIsotopes", value)
+    }
+
+    /// Require charges to match the query.
+    pub fn match_charges(self, value: bool) -> Self {
+        self.option("MatchCharges", value)
+    }
+
+    /// Require tautomers to match the query.
+    pub fn match_tautomers(self, value: bool) -> Self {
+        self.option("MatchTautomers", value)
+    }
+
+    /// Require rings of the query not to be embedded in a larger system.
+    pub fn rings_not_embedded(self, value: bool) -> Self {
+        self.option("RingsNotEmbedded", value)
+    }
+
+    /// Allow single or double bonds to match aromatic bonds.
+    pub fn single_double_bonds_match(self, value: bool) -> Self {
+        self.option("SingleDoubleBondsMatch", value)
+    }
+
+    /// Allow chain bonds in the query to match rings in the hits.
+    pub fn chains_match_rings(self, value: bool) -> Self {
+        self.option("ChainsMatchRings", value)
+    }
+
+    /// Remove explicit hydrogens from the query before searching.
+    pub fn strip_hydrogen(self, value: bool) -> Self {
+        self.option("StripHydrogen", value)
+    }
+
+    /// Set how the stereochemistry of the query should be handled.
+    pub fn stereo(self, mode: StereoMode) -> Self {
+        self.option("Stereo", mode.name())
+    }
+
+    /// Set the level of identity required for the hits of an identity search.
+    ///
+    /// *Only used for the fast variant of the search.*
+    pub fn identity_type(self, identity_type: IdentityType) -> Self {
+        self.option("identity_type", identity_type.name())
+    }
+
+    /// Set the maximum number of records to return.
+    pub fn max_records(self, max_records: u32) -> Self {
+        self.option("MaxRecords", max_records)
+    }
+
+    /// Run the search and retrieve the Compound IDs of the hits.
+    pub fn cids(&self) -> Result<Vec<i32>, Error> {
+        let mut url = format!(
+            "{base}/{dom}/{op}/{ns}/",
+            base = PUG_REST,
+            dom = "compound",
+            op = self.search_type.operation(self.fast),
+            ns = &self.namespace,
+        );
+        if self.fast {
+            url.push_str("cids/");
+        }
+        url.push_str("XML");
+        if !self.options.is_empty() {
+            url.push('?');
+            url.push_str(
+                &form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(self.options.iter())
+                    .finish(),
+            );
+        }
+
+        let response = post(&url, &self.namespace, &self.identifier)?;
+
+        let mut result = SearchResponse::from_api_response_with(response, self.parse_mode)?;
+        let mut polls = 0;
+        loop {
+            match result {
+                SearchResponse::Identifiers(list) => return Ok(list.cids),
+                SearchResponse::Waiting(waiting) if polls >= self.max_polls => {
+                    let message = format!(
+                        "search results for ListKey {} not ready after {} queries",
+                        waiting.list_key, polls
+                    );
+                    return Err(Error::Api(ApiError::Timeout(ErrorContext::from(message))));
+                }
+                SearchResponse::Waiting(waiting) => {
+                    polls += 1;
+                    std::thread::sleep(self.poll_interval);
+                    let url = format!(
+                        "{base}/compound/listkey/{key}/cids/XML",
+                        base = PUG_REST,
+                        key = waiting.list_key,
+                    );
+                    result = SearchResponse::from_api_response_with(get(&url)?, self.parse_mode)?;
+                }
+            }
+        }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A textual format for a chemical structure.
+pub enum StructureFormat {
+    /// A SMILES string.
+    Smiles,
+    /// An InChI string.
+    InChI,
+    /// An SDF record.
+    Sdf,
+}
+
+impl StructureFormat {
+    /// Guess the format of the given structure.
+    ///
+    /// Strings starting with `InChI=` are InChIs, strings with a MOL block
+    /// counts line (as their fourth line) or with a `M  END` or `$$$$` line
+    /// are SDF records, and anything else is assumed to be a SMILES.
+    fn detect(structure: &str) -> Self {
+        let counts = structure.lines().nth(3).map(str::trim_end);
+        let sdf = counts.is_some_and(|l| l.ends_with("V2000") || l.ends_with("V3000"))
+            || structure
+                .lines()
+                .any(|l| matches!(l.trim_end(), "M  END" | "$$$$"));
+        if structure.trim_start().starts_with("InChI=") {
+            StructureFormat::InChI
+        } else if sdf {
+            StructureFormat::Sdf
+        } else {
+            StructureFormat::Smiles
+        }
+    }
+
+    /// Get the name of the PubChem namespace for this format.
+    fn namespace(&self) -> &'static str {
+        match self {
+            StructureFormat::Smiles => "smiles",
+            StructureFormat::InChI => "inchi",
+            StructureFormat::Sdf => "sdf",
+        }
+    }
+
+    /// Get the name of the PubChem output format for this format.
+    fn output(&self) -> &'static str {
+        match self {
+            StructureFormat::Smiles => "SMILES",
+            StructureFormat::InChI => "InChI",
+            StructureFormat::Sdf => "SDF",
+        }
+    }
+
+    /// Get the media type of the PubChem output for this format.
+    fn media_type(&self) -> &'static str {
+        match self {
+            StructureFormat::Smiles | StructureFormat::InChI => "text/plain",
+            StructureFormat::Sdf => "chemical/x-mdl-sdfile",
+        }
+    }
+}
+
+/// Standardize a structure the same way PubChem does before registration.
+///
+/// The format of `input` is detected from its content: InChIs are expected
+/// to start with `InChI=`, SDF records to have a counts line or a `M  END`
+/// line (their MOL block is checked locally, see [`Compound::with_sdf`]),
+/// and anything else is sent as a SMILES. The standardized structure is
+/// returned as a string in the requested `output` format, without trailing
+/// whitespace (and without leading whitespace unless it is an SDF record,
+/// whose first line may be blank).
+///
+/// # Example
+/// ```
+/// use pubchem::StructureFormat;
+///
+/// let inchi = pubchem::standardize("C(=O)(O)C", StructureFormat::InChI).unwrap();
+/// assert_eq!(inchi, "InChI=1S/C2H4O2/c1-2(3)4/h1H3,(H,3,4)");
+/// ```
+pub fn standardize(input: &str, output: StructureFormat) -> Result<String, Error> {
+    let format = StructureFormat::detect(input);
+    if format == StructureFormat::Sdf {
+        utils::validate_sdf(input)?;
+    }
+    let url = format!(
+        "{base}/standardize/{ns}/{out}",
+        base = PUG_REST,
+        ns = format.namespace(),
+        out = output.output(),
+    );
+    let response = post_as(&url, output.media_type(), format.namespace(), input)?;
+    let text = response.into_string()?;
+    let text = match output {
+        StructureFormat::Sdf => text.trim_end(),
+        StructureFormat::Smiles | StructureFormat::InChI => text.trim(),
+    };
+    Ok(text.to_string())
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use crate::error::ApiError;
 
     #[test]
     fn compound_new() {
@@ -407,6 +2361,138 @@ mod tests {
         assert_eq!(compound.title().unwrap(), "Acetone");
     }
 
+    #[test]
+    fn compound_with_sdf() {
+        let sdf = "acetone
+  manual
+
+  4  3  0  0  0  0  0  0  0  0999 V2000
+    0.0000    0.0000    0.0000 C   0  0  0  0  0  0  0  0  0  0  0  0
+    1.2990    0.7500    0.0000 C   0  0  0  0  0  0  0  0  0  0  0  0
+   -1.2990    0.7500    0.0000 C   0  0  0  0  0  0  0  0  0  0  0  0
+    0.0000   -1.5000    0.0000 O   0  0  0  0  0  0  0  0  0  0  0  0
+  1  2  1  0  0  0  0
+  1  3  1  0  0  0  0
+  1  4  2  0  0  0  0
+M  END
+$$$$
+";
+        let compound = Compound::with_sdf(sdf).unwrap();
+        assert_eq!(compound.cids().unwrap(), vec![180]);
+    }
+
+    #[test]
+    fn structure_format_detect() {
+        let sdf = "\n  test\n\n  0  0  0  0  0  0  0  0  0  0999 V2000\nM  END\n";
+        assert_eq!(StructureFormat::detect("CC(=O)O"), StructureFormat::Smiles);
+        assert_eq!(
+            StructureFormat::detect(" InChI=1S/C2H4O2/c1-2(3)4/h1H3,(H,3,4)\n"),
+            StructureFormat::InChI
+        );
+        assert_eq!(StructureFormat::detect(sdf), StructureFormat::Sdf);
+        let v3000 = "acetone\n  test\n\n  0  0  0     0  0            999 V3000\n";
+        assert_eq!(StructureFormat::detect(v3000), StructureFormat::Sdf);
+        assert_eq!(
+            StructureFormat::detect("CC(=O)O\nCCO\n"),
+            StructureFormat::Smiles
+        );
+        assert_eq!(
+            StructureFormat::detect("CC(=O)O\n$$$$\n"),
+            StructureFormat::Sdf
+        );
+    }
+
+    #[test]
+    fn check_response_context() {
+        let xml = r#"<?xml version="1.0"?>
+            <Fault xmlns="http://pubchem.ncbi.nlm.nih.gov/pug_rest">
+                <Code>PUGREST.NotFound</Code>
+                <Message>No CID found</Message>
+                <Details>No CID found that matches the given name</Details>
+            </Fault>
+        "#;
+        let response = ureq::Response::new(404, "Not Found", xml).unwrap();
+        let url = format!("{}/compound/name/cids/XML", PUG_REST);
+        let names = (0..100).map(|i| format!("name{}", i)).join(",");
+        let result = check_response(Err(ureq::Error::Status(404, response)), || {
+            describe_request(&url, Some(("name", &names)))
+        });
+        let error = match result {
+            Err(Error::Api(error @ ApiError::NotFound(_))) => error,
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        };
+        let context = error.context();
+        assert_eq!(context.status, Some(404));
+        assert_eq!(context.details.len(), 1);
+        assert_eq!(
+            error.to_string(),
+            concat!(
+                "not found: No CID found (No CID found that matches the given name) ",
+                "[HTTP 404 for compound/name/cids/XML (name: name0,name1,name2,name3,",
+                "name4,name5,name6,name7,name8,name9,name... ",
+                "(625 more characters))]"
+            )
+        );
+    }
+
+    #[test]
+    fn check_response_throttled() {
+        let response = "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 30\r\n\r\n"
+            .parse::<ureq::Response>()
+            .unwrap();
+        let result = check_response(Err(ureq::Error::Status(429, response)), String::new);
+        match result {
+            Err(Error::Api(ApiError::Throttled(context))) => {
+                assert_eq!(context.status, Some(429));
+                assert_eq!(context.retry_after, Some(Duration::from_secs(30)));
+                assert_eq!(context.message, "Too Many Requests");
+                assert!(context.details.is_empty());
+            }
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn check_response_not_xml() {
+        let html = "<html><body><h1>503 Service Temporarily Unavailable</h1></body></html>";
+        let response = ureq::Response::new(503, "Service Unavailable", html).unwrap();
+        let result = check_response(Err(ureq::Error::Status(503, response)), String::new);
+        match result {
+            Err(Error::Api(ApiError::ServerBusy(context))) => {
+                assert_eq!(context.status, Some(503));
+                assert_eq!(context.message, "Service Unavailable");
+                assert_eq!(context.details, vec![html.to_string()]);
+            }
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn compound_url_options() {
+        let compound = Compound::with_name("aspirin").name_type(NameType::Word);
+        assert_eq!(
+            compound.url("cids", &[("cids_type", CidsType::Parent.name())]),
+            format!(
+                "{}/compound/name/cids/XML?cids_type=parent&name_type=word",
+                PUG_REST
+            )
+        );
+        let compound = Compound::new(2244).name_type(NameType::Word);
+        assert_eq!(
+            compound.url("synonyms", &[]),
+            format!("{}/compound/cid/synonyms/XML", PUG_REST)
+        );
+    }
+
+    #[test]
+    fn compound_with_sdf_invalid() {
+        match Compound::with_sdf("CC(=O)C") {
+            Err(Error::Parse(crate::error::ParseError::Sdf(_))) => (),
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
+    }
+
     #[test]
     fn compound_cids() {
         let compound = Compound::new(2244);
@@ -435,6 +2521,129 @@ mod tests {
         assert_eq!(&synonyms[1], "2-propanone");
     }
 
+    #[test]
+    fn compound_with_xref() {
+        let compound = Compound::with_xref(XrefType::RN, "50-78-2");
+        assert!(compound.cids().unwrap().contains(&2244));
+    }
+
+    #[test]
+    fn compound_xrefs() {
+        let compound = Compound::new(2244);
+        let information = compound.xrefs(&[XrefType::RN, XrefType::PatentID]).unwrap();
+        assert!(information.rns.contains(&String::from("50-78-2")));
+        assert!(!information.patent_ids.is_empty());
+        assert!(information.registry_ids.is_empty());
+    }
+
+    #[test]
+    fn compound_description() {
+        let compound = Compound::new(2244);
+        let descriptions = compound.description().unwrap();
+        assert!(!descriptions.is_empty());
+        assert!(descriptions.iter().all(|d| !d.text.is_empty()));
+    }
+
+    #[test]
+    fn compound_dates() {
+        let compound = Compound::new(2244);
+        let created = compound.dates(DateType::Creation).unwrap().unwrap();
+        assert_eq!(created.year, Some(2005));
+    }
+
+    #[test]
+    fn compound_parent() {
+        let compound = Compound::new(517045);
+        assert_eq!(compound.parent().unwrap(), vec![176]);
+    }
+
+    #[test]
+    fn compound_same_connectivity() {
+        let compound = Compound::new(5950);
+        let cids = compound.same_connectivity().unwrap();
+        assert!(cids.contains(&5950));
+        assert!(cids.contains(&71080));
+    }
+
+    #[test]
+    fn compound_classification() {
+        let compound = Compound::new(2244);
+        let hierarchies = compound.classification().unwrap();
+        let mesh = hierarchies
+            .iter()
+            .find(|h| h.source_name == "MeSH")
+            .expect("aspirin should be classified in MeSH");
+        assert!(mesh.roots().count() > 0);
+    }
+
+    #[test]
+    fn classification_node_cids() {
+        let node = ClassificationNode::new(1856916);
+        assert!(!node.cids().unwrap().is_empty());
+    }
+
+    #[test]
+    fn compound_view_heading() {
+        let compound = Compound::with_name("aspirin");
+        let record = compound.view_heading("Melting Point").unwrap();
+        assert_eq!(record.record_number, 2244);
+        let section = record.section("Melting Point").unwrap();
+        assert!(section.all_information().count() > 0);
+    }
+
+    #[test]
+    fn annotations_page() {
+        let annotations = Annotations::new("Boiling Point").heading_type("Compound");
+        let page = annotations.page(1).unwrap();
+        assert_eq!(page.page, 1);
+        assert!(page.total_pages >= 1);
+        assert!(!page.annotations.is_empty());
+    }
+
+    #[test]
+    fn annotations_iter() {
+        let annotations = Annotations::new("Boiling Point").heading_type("Compound");
+        let first_page = annotations.page(1).unwrap();
+        let n = first_page.annotations.len() + 1;
+        let collected = annotations
+            .iter()
+            .take(n)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(collected.len(), n);
+    }
+
+    #[test]
+    fn annotations_headings() {
+        let headings = Annotations::headings().unwrap();
+        assert!(headings["Compound"].iter().any(|h| h == "Boiling Point"));
+    }
+
+    #[test]
+    fn compound_ghs_classification() {
+        let compound = Compound::new(2244);
+        let ghs = compound.ghs_classification().unwrap();
+        assert!(!ghs.is_empty());
+        assert!(ghs.pictograms().contains(&ghs::Pictogram::GHS07));
+        assert!(ghs.hazard_codes().contains(&ghs::HazardCode::H302));
+    }
+
+    #[test]
+    fn compound_experimental_properties() {
+        use crate::experimental::ExperimentalProperty;
+        let compound = Compound::new(180);
+        let values = compound
+            .experimental_properties(&[ExperimentalProperty::BoilingPoint])
+            .unwrap();
+        assert!(!values.is_empty());
+        let celsius = values
+            .iter()
+            .filter_map(|v| v.measurement.as_ref())
+            .map(|m| m.value())
+            .collect::<Vec<_>>();
+        assert!(celsius.iter().any(|t| (t - 56.0).abs() < 1.0));
+    }
+
     #[test]
     fn compound_name_not_found() {
         let compound = Compound::with_name("none");
@@ -445,6 +2654,316 @@ mod tests {
         }
     }
 
+    #[test]
+    fn compounds_description() {
+        let compounds = Compounds::new([2244, 180]);
+        let descriptions = compounds.description().unwrap();
+        assert_eq!(descriptions.len(), 2);
+        assert_eq!(descriptions[0].0, 2244);
+        assert_eq!(descriptions[1].0, 180);
+    }
+
+    #[test]
+    fn compounds_batch() {
+        let compounds = Compounds::new(0..10).chunk_size(3).concurrency(3);
+        let batch = compounds.batch(|chunk| {
+            if chunk.iter().any(|id| id == "4") {
+                let message = format!("no compound found for {}", chunk.join(","));
+                return Err(Error::Api(ApiError::NotFound(message.into())));
+            }
+            chunk
+                .iter()
+                .map(|id| id.parse().map_err(Error::from))
+                .collect::<Result<Vec<u32>, Error>>()
+        });
+        assert!(!batch.is_complete());
+        assert_eq!(batch.result, vec![0, 1, 2, 6, 7, 8, 9]);
+        assert_eq!(batch.failures.len(), 1);
+        assert_eq!(batch.failures[0].identifiers, vec!["3", "4", "5"]);
+        assert!(matches!(
+            batch.into_result(),
+            Err(Error::Api(ApiError::NotFound(_)))
+        ));
+    }
+
+    #[test]
+    fn compounds_batch_isolate_failures() {
+        // "4" makes the whole request fail, "7" is silently dropped
+        let f = |chunk: &[String]| {
+            if chunk.iter().any(|id| id == "4") {
+                let message = format!("no compound found for {}", chunk.join(","));
+                return Err(Error::Api(ApiError::NotFound(message.into())));
+            }
+            chunk
+                .iter()
+                .filter(|id| *id != "7")
+                .map(|id| id.parse().map_err(Error::from))
+                .collect::<Result<Vec<u32>, Error>>()
+        };
+        let keys = |values: &Vec<u32>| Some(values.iter().map(|x| x.to_string()).collect());
+
+        let compounds = Compounds::new(0..10).chunk_size(3).concurrency(2);
+        let batch = compounds.batch_with_keys(f, keys);
+        assert_eq!(batch.result, vec![0, 1, 2, 6, 8, 9]);
+        assert_eq!(batch.found, vec!["0", "1", "2", "6", "8", "9"]);
+        assert_eq!(batch.missing, vec!["7"]);
+        assert_eq!(batch.failures.len(), 1);
+
+        let batch = compounds.isolate_failures(true).batch_with_keys(f, keys);
+        assert!(batch.is_complete());
+        assert_eq!(batch.result, vec![0, 1, 2, 3, 5, 6, 8, 9]);
+        assert_eq!(batch.found, vec!["0", "1", "2", "3", "5", "6", "8", "9"]);
+        assert_eq!(batch.missing, vec!["4", "7"]);
+    }
+
+    #[test]
+    fn compounds_batch_retry_order() {
+        // "2" is dropped from the results of its chunk, but not when retried
+        let f = |chunk: &[String]| {
+            chunk
+                .iter()
+                .filter(|id| chunk.len() == 1 || *id != "2")
+                .map(|id| id.parse().map_err(Error::from))
+                .collect::<Result<Vec<u32>, Error>>()
+        };
+        let keys = |values: &Vec<u32>| Some(values.iter().map(|x| x.to_string()).collect());
+
+        let compounds = Compounds::new(0..5).isolate_failures(true);
+        let batch = compounds.batch_with_keys(f, keys);
+        assert!(batch.is_complete());
+        assert_eq!(batch.result, vec![0, 1, 2, 3, 4]);
+        assert_eq!(batch.found, vec!["0", "1", "2", "3", "4"]);
+        assert!(batch.missing.is_empty());
+    }
+
+    #[test]
+    fn compounds_resolve_from_table() {
+        let table = [
+            (2244, "BSYNRYMUTXBXSQ-UHFFFAOYSA-N"),
+            (1983, "RZVAJINKPMORJF-UHFFFAOYSA-N"),
+        ]
+        .iter()
+        .map(|&(cid, key)| rest::Properties {
+            cid,
+            inchi_key: Some(key.to_string()),
+            ..Default::default()
+        })
+        .collect::<rest::PropertyTable>();
+        let inputs = [
+            "rzvajinkpmorjf-uhfffaoysa-n",
+            "XXXXXXXXXXXXXX-UHFFFAOYSA-N",
+            "BSYNRYMUTXBXSQ-UHFFFAOYSA-N",
+        ];
+        let compounds = Compounds::with_inchikeys(inputs);
+        let resolved = compounds.resolve_from_table(&compounds.identifiers, table);
+        assert_eq!(resolved[0].0, inputs[0]);
+        assert_eq!(resolved[0].1, Resolved::Found(vec![1983]));
+        assert_eq!(resolved[1].1, Resolved::NotFound);
+        assert_eq!(resolved[2].1.cids(), &[2244]);
+
+        let names = Compounds::with_names(["aspirin", "caffeine"]).chunk_size(100);
+        assert_eq!(names.chunk_size, 1);
+        assert_eq!(Resolved::from_cids(vec![0]), Resolved::NotFound);
+    }
+
+    #[test]
+    fn parse_mode_clients() {
+        let strict = |mode: ParseMode| assert_eq!(mode, ParseMode::Strict);
+        let compound = Compound::new(2244).parse_mode(ParseMode::Strict);
+        strict(compound.resolved().unwrap().parse_mode);
+        let annotations = Annotations::new("Boiling Point").parse_mode(ParseMode::Strict);
+        strict(annotations.parse_mode);
+        let node = ClassificationNode::new(1856916).parse_mode(ParseMode::Strict);
+        strict(node.parse_mode);
+
+        let xml = r#"<?xml version="1.0"?>
+            <Annotations xmlns="http://pubchem.ncbi.nlm.nih.gov/pug_view">
+                <Page>1</Page><NewElement>1</NewElement><TotalPages>1</TotalPages>
+            </Annotations>"#;
+        let response = || ureq::Response::new(200, "OK", xml).unwrap();
+        let page = view::Annotations::from_api_response_with(response(), annotations.parse_mode);
+        assert!(matches!(page, Err(Error::UnexpectedElement { .. })));
+        assert!(view::Annotations::from_api_response_with(response(), ParseMode::Lenient).is_ok());
+
+        let xml = r#"<?xml version="1.0"?>
+            <IdentifierList xmlns="http://pubchem.ncbi.nlm.nih.gov/pug_rest">
+                <CID>2244</CID><NewElement>1</NewElement>
+            </IdentifierList>"#;
+        let response = || ureq::Response::new(200, "OK", xml).unwrap();
+        let list = rest::IdentifierList::from_api_response_with(response(), node.parse_mode);
+        assert!(matches!(list, Err(Error::UnexpectedElement { .. })));
+        assert!(
+            rest::IdentifierList::from_api_response_with(response(), ParseMode::Lenient).is_ok()
+        );
+    }
+
+    #[test]
+    fn element_stream() {
+        let xml = r#"<?xml version="1.0"?>
+            <PropertyTable xmlns="http://pubchem.ncbi.nlm.nih.gov/pug_rest">
+                <Properties><CID>6140</CID><Title>Phenylalanine</Title></Properties>
+                <Properties><CID>6057</CID><Title>Tyrosine</Title></Properties>
+                <Properties><CID>6305</CID><Title>Tryptophan"#;
+        let response = ureq::Response::new(200, "OK", xml).unwrap();
+        let mut stream = ElementStream::<rest::Properties>::new(
+            response,
+            b"PropertyTable",
+            b"Properties",
+            ParseMode::Lenient,
+        )
+        .unwrap();
+        assert_eq!(stream.next().unwrap().unwrap().cid, 6140);
+        assert_eq!(stream.next().unwrap().unwrap().cid, 6057);
+        assert!(stream.next().unwrap().is_err());
+        assert!(stream.next().is_none());
+
+        let response = ureq::Response::new(200, "OK", "<IdentifierList/>").unwrap();
+        let stream = ElementStream::<rest::Properties>::new(
+            response,
+            b"PropertyTable",
+            b"Properties",
+            ParseMode::Lenient,
+        );
+        assert!(stream.is_err());
+    }
+
+    #[test]
+    fn element_stream_unexpected_root() {
+        let xml = r#"<?xml version="1.0"?><Fault><Code>PUGREST.NotFound</Code></Fault>"#;
+        let response = ureq::Response::new(200, "OK", xml).unwrap();
+        let stream = ElementStream::<rest::Properties>::new(
+            response,
+            b"PropertyTable",
+            b"Properties",
+            ParseMode::Lenient,
+        );
+        assert!(matches!(
+            stream,
+            Err(Error::UnexpectedElement { element, .. }) if element == "Fault"
+        ));
+    }
+
+    #[test]
+    fn description_from_information_list() {
+        let list = rest::InformationList {
+            informations: vec![
+                rest::Information {
+                    cids: vec![2244],
+                    title: Some(String::from("Aspirin")),
+                    ..Default::default()
+                },
+                rest::Information {
+                    cids: vec![2244],
+                    description: Some(String::from("Aspirin is a member of benzoic acids.")),
+                    description_source_name: Some(String::from("ChEBI")),
+                    description_url: Some(String::from("https://www.ebi.ac.uk/chebi/")),
+                    ..Default::default()
+                },
+                rest::Information {
+                    cids: vec![180],
+                    title: Some(String::from("Acetone")),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let grouped = Description::from_information_list(list);
+        assert_eq!(grouped.len(), 2);
+        assert_eq!(grouped[0].1.len(), 1);
+        assert_eq!(grouped[0].1[0].source.as_deref(), Some("ChEBI"));
+        assert_eq!(grouped[1], (180, Vec::new()));
+    }
+
+    #[test]
+    fn structure_search_similarity() {
+        let search = StructureSearch::new(StructureSearchType::Similarity, 2244)
+            .threshold(95)
+            .max_records(5);
+        let cids = search.cids().unwrap();
+        assert!(cids.contains(&2244));
+        assert!(cids.len() <= 5);
+    }
+
+    #[test]
+    fn structure_search_identity_slow() {
+        let search = StructureSearch::with_smiles(StructureSearchType::Identity, "CC(=O)C")
+            .fast(false)
+            .poll_interval(Duration::from_millis(500));
+        assert_eq!(search.cids().unwrap(), vec![180]);
+    }
+
+    #[test]
+    fn standardize_smiles() {
+        let smiles = standardize("OC(C)=O", StructureFormat::Smiles).unwrap();
+        assert_eq!(smiles, "CC(=O)O");
+    }
+
+    #[test]
+    fn structure_search_threshold() {
+        let search = StructureSearch::new(StructureSearchType::Similarity, 2244);
+        let search = search.threshold(95).threshold(250);
+        assert_eq!(search.options, vec![("Threshold", String::from("100"))]);
+    }
+
+    #[test]
+    fn structure_search_identity_type() {
+        let search =
+            StructureSearch::with_smiles(StructureSearchType::Identity, "C[C@@H](C(=O)O)N")
+                .identity_type(IdentityType::SameConnectivity);
+        let cids = search.cids().unwrap();
+        assert!(cids.contains(&5950));
+        assert!(cids.contains(&71080));
+    }
+
+    #[test]
+    fn compounds_properties_isolate_failures() {
+        let compounds = Compounds::new([2244, 999_999_999, 180]).isolate_failures(true);
+        let batch = compounds.properties_batch(&[CompoundProperty::Title]);
+        assert!(batch.is_complete());
+        assert_eq!(batch.found, vec!["2244", "180"]);
+        assert_eq!(batch.missing, vec!["999999999"]);
+    }
+
+    #[test]
+    fn compounds_properties_iter() {
+        let compounds = Compounds::new([6140, 6057, 6305]).chunk_size(2);
+        let cids = compounds
+            .properties_iter(&[CompoundProperty::Title])
+            .map(|result| result.map(|p| p.cid))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(cids, vec![6140, 6057, 6305]);
+    }
+
+    #[test]
+    fn compounds_with_inchikeys_cids() {
+        let compounds = Compounds::with_inchikeys([
+            "BSYNRYMUTXBXSQ-UHFFFAOYSA-N",
+            "XXXXXXXXXXXXXX-UHFFFAOYSA-N",
+        ]);
+        let cids = compounds.cids().unwrap();
+        assert_eq!(cids[0].1.cids(), &[2244]);
+        assert_eq!(cids[1].1, Resolved::NotFound);
+    }
+
+    #[test]
+    fn compounds_with_names_cids() {
+        let compounds = Compounds::with_names(["aspirin", "caffeine", "not a compound"]);
+        let cids = compounds.cids().unwrap();
+        assert_eq!(cids[0].1.cids(), &[2244]);
+        assert_eq!(cids[1].1.cids(), &[2519]);
+        assert_eq!(cids[2].1, Resolved::NotFound);
+    }
+
+    #[test]
+    fn compounds_resolve() {
+        let compounds = Compounds::with_smiles(["CC(=O)OC1=CC=CC=C1C(=O)O", "C1CC1CC1CC1"]);
+        let report = compounds.resolve();
+        assert_eq!(report[0].cids, vec![2244]);
+        assert_eq!(report[0].status, resolve::ResolutionStatus::Found);
+        assert_eq!(report[1].input, "C1CC1CC1CC1");
+    }
+
     #[test]
     fn compounds_properties() {
         let compounds = Compounds::new([6140, 6057, 6305]);
@@ -1,2 +1,3 @@
 //! Raw XML data types from PubChem.
 pub mod rest;
+pub mod view;
@@ -1,15 +1,27 @@
 //! Raw data types returned by the Power User Gateway REST API.
 
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
 use std::io::BufRead;
 use std::iter::IntoIterator;
+use std::num::ParseFloatError;
 use std::ops::Deref;
 use std::ops::DerefMut;
+use std::str::FromStr;
 
 use quick_xml::events::BytesStart;
 use quick_xml::Reader;
 
 use crate::error::Error;
+use crate::error::ParseError;
+use crate::fingerprint::PubChemFingerprint;
 use crate::parser::FromXml;
+use crate::parser::ParseMode;
+use crate::CompoundProperty;
+use crate::PropertyValue;
 
 #[derive(Default, Debug, PartialEq)]
 pub struct Fault {
@@ -24,11 +36,12 @@ impl FromXml for Fault {
         event: &BytesStart,
         reader: &mut Reader<B>,
         buffer: &mut Vec<u8>,
+        mode: ParseMode,
     ) -> Result<Self, Error> {
         debug_assert_eq!(event.local_name(), b"Fault");
 
         let mut fault = Fault::default();
-        parse_inner! {event, reader, buffer,
+        parse_inner! {event, reader, buffer, mode,
             b"Code" => {
                 fault.code = reader.read_text(b"Code", buffer)?;
             },
@@ -49,6 +62,29 @@ pub struct Waiting {
     pub message: Option<String>,
 }
 
+impl FromXml for Waiting {
+    #[allow(unused_variables)]
+    fn from_xml<B: BufRead>(
+        event: &BytesStart,
+        reader: &mut Reader<B>,
+        buffer: &mut Vec<u8>,
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        debug_assert_eq!(event.local_name(), b"Waiting");
+
+        let mut waiting = Waiting::default();
+        parse_inner! {event, reader, buffer, mode,
+            b"ListKey" => {
+                waiting.list_key = reader.read_text(b"ListKey", buffer)?;
+            },
+            b"Message" => {
+                waiting.message = Some(reader.read_text(b"Message", buffer)?);
+            }
+        }
+        Ok(waiting)
+    }
+}
+
 #[derive(Default, Debug, PartialEq)]
 pub struct PropertyTable {
     pub properties: Vec<Properties>,
@@ -75,19 +111,34 @@ impl IntoIterator for PropertyTable {
     }
 }
 
+impl Extend<Properties> for PropertyTable {
+    fn extend<I: IntoIterator<Item = Properties>>(&mut self, iter: I) {
+        self.properties.extend(iter)
+    }
+}
+
+impl FromIterator<Properties> for PropertyTable {
+    fn from_iter<I: IntoIterator<Item = Properties>>(iter: I) -> Self {
+        Self {
+            properties: iter.into_iter().collect(),
+        }
+    }
+}
+
 impl FromXml for PropertyTable {
     #[allow(unused_variables)]
     fn from_xml<B: BufRead>(
         event: &BytesStart,
         reader: &mut Reader<B>,
         buffer: &mut Vec<u8>,
+        mode: ParseMode,
     ) -> Result<Self, Error> {
         debug_assert_eq!(event.local_name(), b"PropertyTable");
 
         let mut table = PropertyTable::default();
-        parse_inner! {event, reader, buffer,
+        parse_inner! {event, reader, buffer, mode,
             e @ b"Properties" => {
-                table.properties.push(Properties::from_xml(&e, reader, buffer)?);
+                table.properties.push(Properties::from_xml(&e, reader, buffer, mode)?);
             }
         };
 
@@ -95,19 +146,75 @@ impl FromXml for PropertyTable {
     }
 }
 
+/// A decimal number, keeping the exact representation returned by PubChem.
+///
+/// Comparisons are done on the numeric value, so `Decimal`s with different
+/// representations of the same number (such as `180.16` and `180.160`) are
+/// equal.
+#[derive(Debug, Clone)]
+pub struct Decimal {
+    repr: String,
+    value: f64,
+}
+
+impl Decimal {
+    /// Get the numeric value of the decimal.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Get the exact textual representation of the decimal.
+    pub fn as_str(&self) -> &str {
+        &self.repr
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(&self.repr)
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = ParseFloatError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let repr = s.trim().to_string();
+        let value = repr.parse()?;
+        Ok(Self { repr, value })
+    }
+}
+
+impl From<Decimal> for f64 {
+    fn from(decimal: Decimal) -> f64 {
+        decimal.value
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
 #[derive(Default, Debug, PartialEq)]
 pub struct Properties {
     pub cid: i32,
     pub molecular_formula: Option<String>,
-    pub molecular_weight: Option<String>,
+    pub molecular_weight: Option<Decimal>,
     pub canonical_smiles: Option<String>,
     pub isomeric_smiles: Option<String>,
     pub inchi: Option<String>,
     pub inchi_key: Option<String>,
     pub iupac_name: Option<String>,
     pub xlogp: Option<f64>,
-    pub exact_mass: Option<String>,
-    pub monoisotopic_mass: Option<String>,
+    pub exact_mass: Option<Decimal>,
+    pub monoisotopic_mass: Option<Decimal>,
     pub tpsa: Option<f64>,
     pub complexity: Option<i32>,
     pub charge: Option<i32>,
@@ -135,10 +242,108 @@ pub struct Properties {
     pub feature_ring_count_3d: Option<i32>,
     pub feature_hydrophobe_count_3d: Option<i32>,
     pub conformer_model_rmsd_3d: Option<f64>,
+    /// *The effective rotor count is a weighted sum, and may be fractional.*
     pub effective_rotor_count_3d: Option<f64>,
     pub conformer_count_3d: Option<i32>,
     pub fingerprint_2d: Option<String>,
     pub title: Option<String>,
+    /// The text of the elements unknown to the parser, by element name.
+    pub extra: BTreeMap<String, Vec<String>>,
+}
+
+impl Properties {
+    /// Get the value of the given property, if it was retrieved.
+    ///
+    /// The base64-encoded `Fingerprint2D` property is decoded, and an
+    /// error is returned if it could not be decoded.
+    ///
+    /// # Example
+    /// ```
+    /// use pubchem::CompoundProperty;
+    ///
+    /// let requested = [CompoundProperty::Title, CompoundProperty::ExactMass];
+    /// let properties = pubchem::Compound::new(2244).properties(&requested).unwrap();
+    /// for property in &requested {
+    ///     println!("{}: {:?}", property.name(), properties.get(property).unwrap());
+    /// }
+    /// ```
+    pub fn get(&self, property: &CompoundProperty) -> Result<Option<PropertyValue>, ParseError> {
+        use CompoundProperty::*;
+
+        let string = |s: &Option<String>| s.clone().map(PropertyValue::String);
+        let integer = |i: &Option<i32>| i.map(PropertyValue::Integer);
+        let float = |f: &Option<f64>| f.map(PropertyValue::Float);
+        let decimal = |d: &Option<Decimal>| d.clone().map(PropertyValue::Decimal);
+
+        let value = match property {
+            MolecularFormula => string(&self.molecular_formula),
+            MolecularWeight => decimal(&self.molecular_weight),
+            CanonicalSMILES => string(&self.canonical_smiles),
+            IsomericSMILES => string(&self.isomeric_smiles),
+            InChI => string(&self.inchi),
+            InChIKey => string(&self.inchi_key),
+            IUPACName => string(&self.iupac_name),
+            Title => string(&self.title),
+            XLogP => float(&self.xlogp),
+            ExactMass => decimal(&self.exact_mass),
+            MonoisotopicMass => decimal(&self.monoisotopic_mass),
+            TPSA => float(&self.tpsa),
+            Complexity => integer(&self.complexity),
+            Charge => integer(&self.charge),
+            HBondDonorCount => integer(&self.hbond_donor_count),
+            HBondAcceptorCount => integer(&self.hbond_acceptor_count),
+            RotatableBondCount => integer(&self.rotatable_bond_count),
+            HeavyAtomCount => integer(&self.heavy_atom_count),
+            IsotopeAtomCount => integer(&self.isotope_atom_count),
+            AtomStereoCount => integer(&self.atom_stereo_count),
+            DefinedAtomStereoCount => integer(&self.defined_atom_stereo_count),
+            UndefinedAtomStereoCount => integer(&self.undefined_atom_stereo_count),
+            BondStereoCount => integer(&self.bond_stereo_count),
+            DefinedBondStereoCount => integer(&self.defined_bond_stereo_count),
+            UndefinedBondStereoCount => integer(&self.undefined_bond_stereo_count),
+            CovalentUnitCount => integer(&self.covalent_unit_count),
+            Volume3D => float(&self.volume_3d),
+            XStericQuadrupole3D => float(&self.x_steric_quadrupole_3d),
+            YStericQuadrupole3D => float(&self.y_steric_quadrupole_3d),
+            ZStericQuadrupole3D => float(&self.z_steric_quadrupole_3d),
+            FeatureCount3D => integer(&self.feature_count_3d),
+            FeatureAcceptorCount3D => integer(&self.feature_acceptor_count_3d),
+            FeatureDonorCount3D => integer(&self.feature_donor_count_3d),
+            FeatureAnionCount3D => integer(&self.feature_anion_count_3d),
+            FeatureCationCount3D => integer(&self.feature_cation_count_3d),
+            FeatureRingCount3D => integer(&self.feature_ring_count_3d),
+            FeatureHydrophobeCount3D => integer(&self.feature_hydrophobe_count_3d),
+            ConformerModelRMSD3D => float(&self.conformer_model_rmsd_3d),
+            EffectiveRotorCount3D => float(&self.effective_rotor_count_3d),
+            ConformerCount3D => integer(&self.conformer_count_3d),
+            Fingerprint2D => {
+                let fingerprint = self.fingerprint().transpose()?;
+                fingerprint.map(PropertyValue::Fingerprint)
+            }
+        };
+        Ok(value)
+    }
+
+    /// Decode the `Fingerprint2D` property, if it was retrieved.
+    pub fn fingerprint(&self) -> Option<Result<PubChemFingerprint, ParseError>> {
+        self.fingerprint_2d
+            .as_deref()
+            .map(PubChemFingerprint::from_base64)
+    }
+
+    /// Iterate over all the properties that were retrieved, with their values.
+    ///
+    /// An error is yielded for properties that could not be decoded, see
+    /// [`Properties::get`].
+    pub fn values(
+        &self,
+    ) -> impl Iterator<Item = Result<(CompoundProperty, PropertyValue), ParseError>> + '_ {
+        CompoundProperty::variants().iter().filter_map(move |p| {
+            self.get(p)
+                .transpose()
+                .map(|value| value.map(|value| (*p, value)))
+        })
+    }
 }
 
 impl FromXml for Properties {
@@ -147,6 +352,7 @@ impl FromXml for Properties {
         event: &BytesStart,
         reader: &mut Reader<B>,
         buffer: &mut Vec<u8>,
+        mode: ParseMode,
     ) -> Result<Self, Error> {
         debug_assert_eq!(event.local_name(), b"Properties");
 
@@ -160,18 +366,18 @@ impl FromXml for Properties {
         }
 
         let mut p = Properties::default();
-        parse_inner! {event, reader, buffer,
+        parse_inner! {event, reader, buffer, mode,
             b"CID" => { p.cid = reader.read_text(b"CID", buffer)?.parse()?; },
             e @ b"MolecularFormula" => to_field!(reader, buffer, e, p.molecular_formula),
-            e @ b"MolecularWeight" => to_field!(reader, buffer, e, p.molecular_weight),
+            e @ b"MolecularWeight" => to_field!(reader, buffer, e, p.molecular_weight ?),
             e @ b"CanonicalSMILES" => to_field!(reader, buffer, e, p.canonical_smiles),
             e @ b"IsomericSMILES" => to_field!(reader, buffer, e, p.isomeric_smiles),
             e @ b"InChI" => to_field!(reader, buffer, e, p.inchi),
             e @ b"InChIKey" => to_field!(reader, buffer, e, p.inchi_key),
             e @ b"IUPACName" => to_field!(reader, buffer, e, p.iupac_name),
             e @ b"XLogP" => to_field!(reader, buffer, e, p.xlogp ?),
-            e @ b"ExactMass" => to_field!(reader, buffer, e, p.exact_mass),
-            e @ b"MonoisotopicMass" => to_field!(reader, buffer, e, p.monoisotopic_mass),
+            e @ b"ExactMass" => to_field!(reader, buffer, e, p.exact_mass ?),
+            e @ b"MonoisotopicMass" => to_field!(reader, buffer, e, p.monoisotopic_mass ?),
             e @ b"TPSA" => to_field!(reader, buffer, e, p.tpsa ?),
             e @ b"Complexity" => to_field!(reader, buffer, e, p.complexity ?),
             e @ b"Charge" => to_field!(reader, buffer, e, p.charge ?),
@@ -203,6 +409,7 @@ impl FromXml for Properties {
             e @ b"ConformerCount3D" => to_field!(reader, buffer, e, p.conformer_count_3d ?),
             e @ b"Fingerprint2D" => to_field!(reader, buffer, e, p.fingerprint_2d),
             e @ b"Title" => to_field!(reader, buffer, e, p.title),
+            e @ _ => push_extra!(event, reader, mode, e, p.extra),
         }
 
         Ok(p)
@@ -222,19 +429,20 @@ impl FromXml for InformationList {
         event: &BytesStart,
         reader: &mut Reader<B>,
         buffer: &mut Vec<u8>,
+        mode: ParseMode,
     ) -> Result<Self, Error> {
         debug_assert_eq!(event.local_name(), b"InformationList");
 
         let mut list = InformationList::default();
-        parse_inner! {event, reader, buffer,
+        parse_inner! {event, reader, buffer, mode,
             e @ b"SourceName" => {
                 list.source_names.push(reader.read_text(e.name(), buffer)?);
             },
             e @ b"Information" => {
-                list.informations.push(Information::from_xml(&e, reader, buffer)?);
+                list.informations.push(Information::from_xml(&e, reader, buffer, mode)?);
             },
             e @ b"Annotation" => {
-                list.annotations.push(Annotation::from_xml(&e, reader, buffer)?);
+                list.annotations.push(Annotation::from_xml(&e, reader, buffer, mode)?);
             }
         };
 
@@ -266,7 +474,7 @@ pub struct Information {
     pub taxonomy_ids: Vec<i32>,
     pub mim_ids: Vec<i32>,
     pub probe_ids: Vec<i32>,
-    pub patent_ids: Vec<i32>,
+    pub patent_ids: Vec<String>,
     pub protein_names: Vec<String>,
     pub gene_symbols: Vec<String>,
     pub source_names: Vec<String>,
@@ -277,6 +485,8 @@ pub struct Information {
     pub description_url: Option<String>,
     pub conformer_ids: Vec<String>,
     pub protein_accessions: Vec<String>,
+    /// The text of the elements unknown to the parser, by element name.
+    pub extra: BTreeMap<String, Vec<String>>,
 }
 
 impl FromXml for Information {
@@ -285,6 +495,7 @@ impl FromXml for Information {
         event: &BytesStart,
         reader: &mut Reader<B>,
         buffer: &mut Vec<u8>,
+        mode: ParseMode,
     ) -> Result<Self, Error> {
         debug_assert_eq!(event.local_name(), b"Information");
 
@@ -303,12 +514,12 @@ impl FromXml for Information {
                 $p.$field = Some(reader.read_text($e.name(), $buffer)?);
             }};
             ($reader:ident, $buffer:ident, $e:ident, $p:ident . $field:ident XML) => {{
-                $p.$field = Some(FromXml::from_xml(&$e, $reader, $buffer)?);
+                $p.$field = Some(FromXml::from_xml(&$e, $reader, $buffer, mode)?);
             }};
         }
 
         let mut i = Information::default();
-        parse_inner! {event, reader, buffer,
+        parse_inner! {event, reader, buffer, mode,
             e @ b"ID" => {
                 i.id = reader.read_text(e.name(), buffer)?.parse()?;
             },
@@ -324,6 +535,7 @@ impl FromXml for Information {
             e @ b"HoldDate" => set_option!(reader, buffer, e, i.hold_date XML),
             e @ b"RegistryID" => push_field!(reader, buffer, e, i.registry_ids),
             e @ b"RN" => push_field!(reader, buffer, e, i.rns),
+            e @ b"PubMedID" => push_field!(reader, buffer, e, i.pubmed_ids ?),
             e @ b"PubMedId" => push_field!(reader, buffer, e, i.pubmed_ids ?),
             e @ b"MMDBID" => push_field!(reader, buffer, e, i.mmdb_ids ?),
             e @ b"DBURL" => push_field!(reader, buffer, e, i.db_urls),
@@ -333,7 +545,7 @@ impl FromXml for Information {
             e @ b"TaxonomyID" => push_field!(reader, buffer, e, i.taxonomy_ids ?),
             e @ b"MIMID" => push_field!(reader, buffer, e, i.mim_ids ?),
             e @ b"ProbeID" => push_field!(reader, buffer, e, i.probe_ids ?),
-            e @ b"PatentID" => push_field!(reader, buffer, e, i.patent_ids ?),
+            e @ b"PatentID" => push_field!(reader, buffer, e, i.patent_ids),
             e @ b"ProteinName" => push_field!(reader, buffer, e, i.protein_names),
             e @ b"GeneSymbol" => push_field!(reader, buffer, e, i.gene_symbols),
             e @ b"SourceName" => push_field!(reader, buffer, e, i.source_names),
@@ -344,6 +556,7 @@ impl FromXml for Information {
             e @ b"DescriptionURL" => set_option!(reader, buffer, e, i.description_url),
             e @ b"ConformerID" => push_field!(reader, buffer, e, i.conformer_ids),
             e @ b"ProteinAccession" => push_field!(reader, buffer, e, i.protein_accessions),
+            e @ _ => push_extra!(event, reader, mode, e, i.extra),
         }
 
         Ok(i)
@@ -362,6 +575,7 @@ impl FromXml for Annotation {
         event: &BytesStart,
         reader: &mut Reader<B>,
         buffer: &mut Vec<u8>,
+        mode: ParseMode,
     ) -> Result<Self, Error> {
         debug_assert_eq!(event.local_name(), b"Annotation");
 
@@ -369,7 +583,7 @@ impl FromXml for Annotation {
             heading: String::new(),
             ty: String::new(),
         };
-        parse_inner! {event, reader, buffer,
+        parse_inner! {event, reader, buffer, mode,
             e @ b"Heading" => {
                 a.heading = reader.read_text(e.name(), buffer)?;
             },
@@ -382,14 +596,289 @@ impl FromXml for Annotation {
     }
 }
 
-#[derive(Debug, Default, PartialEq, Eq)]
+/// A date, with an optional time, as reported by PubChem.
+///
+/// PubChem dates may only be partially specified, so each component is
+/// optional, but a component can only be set if the components before it
+/// are set too (a month without a year is invalid, for instance). Dates
+/// parsed from PubChem responses are kept as reported, and only checked by
+/// [`DateTime::validate`] and the conversions to other date types. Dates are
+/// ordered chronologically, with a less precise date ordered before a more
+/// precise date sharing the same components.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DateTime {
-    year: Option<i32>,
-    month: Option<i32>,
-    day: Option<i32>,
-    hour: Option<i32>,
-    minute: Option<i32>,
-    second: Option<i32>,
+    pub year: Option<i32>,
+    pub month: Option<i32>,
+    pub day: Option<i32>,
+    pub hour: Option<i32>,
+    pub minute: Option<i32>,
+    pub second: Option<i32>,
+}
+
+impl DateTime {
+    /// Create a new date from a year, month and day.
+    pub fn date(year: i32, month: i32, day: i32) -> Result<Self, ParseError> {
+        let dt = DateTime {
+            year: Some(year),
+            month: Some(month),
+            day: Some(day),
+            ..Default::default()
+        };
+        dt.validate().map(|_| dt)
+    }
+
+    /// Set the time of the date.
+    pub fn with_time(self, hour: i32, minute: i32, second: i32) -> Result<Self, ParseError> {
+        let dt = DateTime {
+            hour: Some(hour),
+            minute: Some(minute),
+            second: Some(second),
+            ..self
+        };
+        dt.validate().map(|_| dt)
+    }
+
+    /// Check that the components of the date form a valid date.
+    pub fn validate(&self) -> Result<(), ParseError> {
+        let components = [
+            ("year", self.year),
+            ("month", self.month),
+            ("day", self.day),
+            ("hour", self.hour),
+            ("minute", self.minute),
+            ("second", self.second),
+        ];
+        for pair in components.windows(2) {
+            if let ((name, None), (next, Some(_))) = (pair[0], pair[1]) {
+                let msg = format!("{} given without {}", next, name);
+                return Err(ParseError::DateTime(msg));
+            }
+        }
+
+        let max_day = match (self.year, self.month) {
+            (Some(y), Some(2)) if y % 4 == 0 && (y % 100 != 0 || y % 400 == 0) => 29,
+            (_, Some(2)) => 28,
+            (_, Some(4 | 6 | 9 | 11)) => 30,
+            _ => 31,
+        };
+        let ranges = [
+            ("month", self.month, 1, 12),
+            ("day", self.day, 1, max_day),
+            ("hour", self.hour, 0, 23),
+            ("minute", self.minute, 0, 59),
+            ("second", self.second, 0, 59),
+        ];
+        for (name, value, min, max) in ranges.iter() {
+            if let Some(v) = value {
+                if v < min || v > max {
+                    let msg = format!("{} out of range: {}", name, v);
+                    return Err(ParseError::DateTime(msg));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for DateTime {
+    /// Format the date in ISO-8601 format, up to its precision.
+    ///
+    /// Negative years are written with a minus sign before their four
+    /// digits (such as `-0005`), and a date without any component is
+    /// written as an empty string.
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self.year {
+            Some(year) if year < 0 => write!(f, "-{:04}", year.unsigned_abs())?,
+            Some(year) => write!(f, "{:04}", year)?,
+            None => (),
+        }
+        if let Some(month) = self.month {
+            write!(f, "-{:02}", month)?;
+        }
+        if let Some(day) = self.day {
+            write!(f, "-{:02}", day)?;
+        }
+        if let Some(hour) = self.hour {
+            write!(f, "T{:02}", hour)?;
+        }
+        if let Some(minute) = self.minute {
+            write!(f, ":{:02}", minute)?;
+        }
+        if let Some(second) = self.second {
+            write!(f, ":{:02}", second)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = ParseError;
+    /// Parse a date in ISO-8601 format, such as `2005-03-26T14:02:05`.
+    ///
+    /// Partial dates (such as `2005`, `2005-03` or `2005-03-26T14`) and
+    /// negative years (such as `-0005`) are supported, and an empty string
+    /// is parsed as a date without any component.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseError::DateTime(format!("invalid ISO-8601 date: {:?}", s));
+        if s.is_empty() {
+            return Ok(DateTime::default());
+        }
+
+        let (date, time) = match s.split_once('T') {
+            Some((date, time)) => (date, Some(time)),
+            None => (s, None),
+        };
+        let (negative, date) = match date.strip_prefix('-') {
+            Some(date) => (true, date),
+            None => (false, date),
+        };
+        let mut parts = date.split('-');
+        let year = parts.next().ok_or_else(invalid)?;
+        if !year.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let year = if negative {
+            format!("-{}", year).parse()?
+        } else {
+            year.parse()?
+        };
+        let mut dt = DateTime {
+            year: Some(year),
+            month: parts.next().map(str::parse).transpose()?,
+            day: parts.next().map(str::parse).transpose()?,
+            ..Default::default()
+        };
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+
+        if let Some(time) = time {
+            if dt.day.is_none() {
+                return Err(invalid());
+            }
+            let mut parts = time.split(':');
+            dt.hour = Some(parts.next().ok_or_else(invalid)?.parse()?);
+            dt.minute = parts.next().map(str::parse).transpose()?;
+            dt.second = parts.next().map(str::parse).transpose()?;
+            if parts.next().is_some() {
+                return Err(invalid());
+            }
+        }
+
+        dt.validate().map(|_| dt)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<DateTime> for chrono::NaiveDate {
+    type Error = ParseError;
+    fn try_from(dt: DateTime) -> Result<Self, Self::Error> {
+        dt.validate()?;
+        match (dt.year, dt.month, dt.day) {
+            (Some(y), Some(m), Some(d)) => chrono::NaiveDate::from_ymd_opt(y, m as u32, d as u32)
+                .ok_or_else(|| ParseError::DateTime(format!("date out of range: {}", dt))),
+            _ => Err(ParseError::DateTime(format!("incomplete date: {}", dt))),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<DateTime> for chrono::NaiveDateTime {
+    type Error = ParseError;
+    /// Convert the date to a `NaiveDateTime`, with missing time components set to zero.
+    fn try_from(dt: DateTime) -> Result<Self, Self::Error> {
+        let date = chrono::NaiveDate::try_from(dt)?;
+        let time = chrono::NaiveTime::from_hms_opt(
+            dt.hour.unwrap_or(0) as u32,
+            dt.minute.unwrap_or(0) as u32,
+            dt.second.unwrap_or(0) as u32,
+        )
+        .ok_or_else(|| ParseError::DateTime(format!("time out of range: {}", dt)))?;
+        Ok(date.and_time(time))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDate> for DateTime {
+    fn from(date: chrono::NaiveDate) -> Self {
+        use chrono::Datelike;
+        DateTime {
+            year: Some(date.year()),
+            month: Some(date.month() as i32),
+            day: Some(date.day() as i32),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDateTime> for DateTime {
+    /// Convert a `NaiveDateTime` to a date, discarding sub-second precision.
+    fn from(datetime: chrono::NaiveDateTime) -> Self {
+        use chrono::Timelike;
+        DateTime {
+            hour: Some(datetime.hour() as i32),
+            minute: Some(datetime.minute() as i32),
+            second: Some(datetime.second().min(59) as i32),
+            ..DateTime::from(datetime.date())
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<DateTime> for time::Date {
+    type Error = ParseError;
+    fn try_from(dt: DateTime) -> Result<Self, Self::Error> {
+        dt.validate()?;
+        match (dt.year, dt.month, dt.day) {
+            (Some(y), Some(m), Some(d)) => time::Month::try_from(m as u8)
+                .and_then(|m| time::Date::from_calendar_date(y, m, d as u8))
+                .map_err(|e| ParseError::DateTime(e.to_string())),
+            _ => Err(ParseError::DateTime(format!("incomplete date: {}", dt))),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<DateTime> for time::PrimitiveDateTime {
+    type Error = ParseError;
+    /// Convert the date to a `PrimitiveDateTime`, with missing time components set to zero.
+    fn try_from(dt: DateTime) -> Result<Self, Self::Error> {
+        let date = time::Date::try_from(dt)?;
+        let time = time::Time::from_hms(
+            dt.hour.unwrap_or(0) as u8,
+            dt.minute.unwrap_or(0) as u8,
+            dt.second.unwrap_or(0) as u8,
+        )
+        .map_err(|e| ParseError::DateTime(e.to_string()))?;
+        Ok(time::PrimitiveDateTime::new(date, time))
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<time::Date> for DateTime {
+    fn from(date: time::Date) -> Self {
+        DateTime {
+            year: Some(date.year()),
+            month: Some(u8::from(date.month()) as i32),
+            day: Some(date.day() as i32),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<time::PrimitiveDateTime> for DateTime {
+    /// Convert a `PrimitiveDateTime` to a date, discarding sub-second precision.
+    fn from(datetime: time::PrimitiveDateTime) -> Self {
+        DateTime {
+            hour: Some(datetime.hour() as i32),
+            minute: Some(datetime.minute() as i32),
+            second: Some(datetime.second() as i32),
+            ..DateTime::from(datetime.date())
+        }
+    }
 }
 
 impl FromXml for DateTime {
@@ -398,8 +887,10 @@ impl FromXml for DateTime {
         event: &BytesStart,
         reader: &mut Reader<B>,
         buffer: &mut Vec<u8>,
+        mode: ParseMode,
     ) -> Result<Self, Error> {
-        debug_assert_eq!(event.local_name(), b"DateTime");
+        // `DateTime` is the type of the `DepositionDate`, `ModificationDate`,
+        // `CreationDate` and `HoldDate` elements, not an element itself.
 
         macro_rules! to_field {
             ($reader:ident, $buffer:ident, $e:ident, $dt:ident . $field:ident) => {{
@@ -408,7 +899,7 @@ impl FromXml for DateTime {
         }
 
         let mut dt = DateTime::default();
-        parse_inner! {event, reader, buffer,
+        parse_inner! {event, reader, buffer, mode,
             e @ b"Year" => to_field!(reader, buffer, e, dt.year),
             e @ b"Month" => to_field!(reader, buffer, e, dt.month),
             e @ b"Day" => to_field!(reader, buffer, e, dt.day),
@@ -441,11 +932,12 @@ impl FromXml for IdentifierList {
         event: &BytesStart,
         reader: &mut Reader<B>,
         buffer: &mut Vec<u8>,
+        mode: ParseMode,
     ) -> Result<Self, Error> {
         debug_assert_eq!(event.local_name(), b"IdentifierList");
 
         let mut list = IdentifierList::default();
-        parse_inner! {event, reader, buffer,
+        parse_inner! {event, reader, buffer, mode,
             b"CID" => {
                 list.cids.push(reader.read_text(b"CID", buffer)?.parse()?);
             },
@@ -453,7 +945,7 @@ impl FromXml for IdentifierList {
                 list.sids.push(reader.read_text(b"SID", buffer)?.parse()?);
             },
             b"AID" => {
-                list.sids.push(reader.read_text(b"AID", buffer)?.parse()?);
+                list.aids.push(reader.read_text(b"AID", buffer)?.parse()?);
             },
             b"ListKey" => {
                 list.list_key = Some(reader.read_text(b"ListKey", buffer)?);
@@ -482,8 +974,607 @@ impl FromXml for IdentifierList {
     }
 }
 
+#[derive(Default, Debug, PartialEq)]
+pub struct Hierarchies {
+    pub hierarchies: Vec<Hierarchy>,
+}
+
+impl Deref for Hierarchies {
+    type Target = Vec<Hierarchy>;
+    fn deref(&self) -> &Self::Target {
+        &self.hierarchies
+    }
+}
+
+impl DerefMut for Hierarchies {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.hierarchies
+    }
+}
+
+impl IntoIterator for Hierarchies {
+    type Item = Hierarchy;
+    type IntoIter = <Vec<Hierarchy> as IntoIterator>::IntoIter;
+    fn into_iter(self) -> Self::IntoIter {
+        self.hierarchies.into_iter()
+    }
+}
+
+impl FromXml for Hierarchies {
+    #[allow(unused_variables)]
+    fn from_xml<B: BufRead>(
+        event: &BytesStart,
+        reader: &mut Reader<B>,
+        buffer: &mut Vec<u8>,
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        debug_assert_eq!(event.local_name(), b"Hierarchies");
+
+        let mut hierarchies = Hierarchies::default();
+        parse_inner! {event, reader, buffer, mode,
+            e @ b"Hierarchy" => {
+                hierarchies.hierarchies.push(Hierarchy::from_xml(&e, reader, buffer, mode)?);
+            }
+        };
+
+        Ok(hierarchies)
+    }
+}
+
+/// A classification tree from a single source, such as MeSH or ChEBI.
+#[derive(Default, Debug, PartialEq)]
+pub struct Hierarchy {
+    pub source_name: String,
+    pub source_id: String,
+    pub root_id: String,
+    pub hid: Option<i32>,
+    pub information: Option<NodeInformation>,
+    pub nodes: Vec<Node>,
+}
+
+impl Hierarchy {
+    /// Get the node with the given node ID, if any.
+    pub fn node(&self, node_id: &str) -> Option<&Node> {
+        self.nodes.iter().find(|node| node.node_id == node_id)
+    }
+
+    /// Iterate over the nodes directly under the root of the hierarchy.
+    pub fn roots(&self) -> impl Iterator<Item = &Node> {
+        self.nodes
+            .iter()
+            .filter(move |node| node.parent_ids.contains(&self.root_id))
+    }
+
+    /// Iterate over the children of the given node.
+    pub fn children<'a>(&'a self, node: &'a Node) -> impl Iterator<Item = &'a Node> {
+        node.child_ids.iter().filter_map(move |id| self.node(id))
+    }
+
+    /// Iterate over the parents of the given node.
+    ///
+    /// *The root of the hierarchy is not a node, and is never yielded.*
+    pub fn parents<'a>(&'a self, node: &'a Node) -> impl Iterator<Item = &'a Node> {
+        node.parent_ids.iter().filter_map(move |id| self.node(id))
+    }
+}
+
+impl FromXml for Hierarchy {
+    #[allow(unused_variables)]
+    fn from_xml<B: BufRead>(
+        event: &BytesStart,
+        reader: &mut Reader<B>,
+        buffer: &mut Vec<u8>,
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        debug_assert_eq!(event.local_name(), b"Hierarchy");
+
+        let mut h = Hierarchy::default();
+        parse_inner! {event, reader, buffer, mode,
+            e @ b"SourceName" => {
+                h.source_name = reader.read_text(e.name(), buffer)?;
+            },
+            e @ b"SourceID" => {
+                h.source_id = reader.read_text(e.name(), buffer)?;
+            },
+            e @ b"RootID" => {
+                h.root_id = reader.read_text(e.name(), buffer)?;
+            },
+            e @ b"HID" => {
+                h.hid = Some(reader.read_text(e.name(), buffer)?.parse()?);
+            },
+            e @ b"Information" => {
+                h.information = Some(NodeInformation::from_xml(&e, reader, buffer, mode)?);
+            },
+            e @ b"Node" => {
+                h.nodes.push(Node::from_xml(&e, reader, buffer, mode)?);
+            },
+        };
+
+        Ok(h)
+    }
+}
+
+/// A single node of a classification hierarchy.
+#[derive(Default, Debug, PartialEq)]
+pub struct Node {
+    pub node_id: String,
+    pub parent_ids: Vec<String>,
+    pub child_ids: Vec<String>,
+    pub information: NodeInformation,
+}
+
+impl FromXml for Node {
+    #[allow(unused_variables)]
+    fn from_xml<B: BufRead>(
+        event: &BytesStart,
+        reader: &mut Reader<B>,
+        buffer: &mut Vec<u8>,
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        debug_assert_eq!(event.local_name(), b"Node");
+
+        let mut node = Node::default();
+        parse_inner! {event, reader, buffer, mode,
+            e @ b"NodeID" => {
+                node.node_id = reader.read_text(e.name(), buffer)?;
+            },
+            e @ b"ParentID" => {
+                node.parent_ids.push(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"ChildID" => {
+                node.child_ids.push(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"Information" => {
+                node.information = NodeInformation::from_xml(&e, reader, buffer, mode)?;
+            },
+        };
+
+        Ok(node)
+    }
+}
+
+/// The information attached to a classification node.
+#[derive(Default, Debug, PartialEq)]
+pub struct NodeInformation {
+    pub name: String,
+    pub descriptions: Vec<String>,
+    pub comments: Vec<String>,
+    pub url: Option<String>,
+    pub hnid: Option<i32>,
+    pub child_ids: Vec<String>,
+    pub has_counts_of_type: Vec<String>,
+    pub counts: Vec<Count>,
+}
+
+impl FromXml for NodeInformation {
+    #[allow(unused_variables)]
+    fn from_xml<B: BufRead>(
+        event: &BytesStart,
+        reader: &mut Reader<B>,
+        buffer: &mut Vec<u8>,
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        debug_assert_eq!(event.local_name(), b"Information");
+
+        let mut i = NodeInformation::default();
+        parse_inner! {event, reader, buffer, mode,
+            e @ b"Name" => {
+                i.name = reader.read_text(e.name(), buffer)?;
+            },
+            e @ b"Description" => {
+                i.descriptions.push(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"Comments" => {
+                i.comments.push(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"URL" => {
+                i.url = Some(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"HNID" => {
+                i.hnid = Some(reader.read_text(e.name(), buffer)?.parse()?);
+            },
+            e @ b"ChildID" => {
+                i.child_ids.push(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"HasCountsOfType" => {
+                i.has_counts_of_type.push(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"Counts" => {
+                i.counts.push(Count::from_xml(&e, reader, buffer, mode)?);
+            },
+        };
+
+        Ok(i)
+    }
+}
+
+/// The number of records of a given type under a classification node.
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct Count {
+    pub ty: String,
+    pub count: i32,
+}
+
+impl FromXml for Count {
+    #[allow(unused_variables)]
+    fn from_xml<B: BufRead>(
+        event: &BytesStart,
+        reader: &mut Reader<B>,
+        buffer: &mut Vec<u8>,
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        debug_assert_eq!(event.local_name(), b"Counts");
+
+        let mut c = Count::default();
+        parse_inner! {event, reader, buffer, mode,
+            e @ b"Type" => {
+                c.ty = reader.read_text(e.name(), buffer)?;
+            },
+            e @ b"Count" => {
+                c.count = reader.read_text(e.name(), buffer)?.parse()?;
+            },
+        };
+
+        Ok(c)
+    }
+}
+
 // #[derive(Debug, PartialEq, Eq)]
 // pub struct Table {
 //     columns: Vec<String>,
 //     rows: Vec<String>
 // }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::parser::FromApiResponse;
+
+    fn response(body: &str) -> ureq::Response {
+        ureq::Response::new(200, "OK", body).unwrap()
+    }
+
+    #[test]
+    fn information_list_xrefs() {
+        let xml = r#"<?xml version="1.0"?>
+            <InformationList xmlns="http://pubchem.ncbi.nlm.nih.gov/pug_rest">
+                <Information>
+                    <CID>2244</CID>
+                    <RN>50-78-2</RN>
+                    <RN>11126-35-5</RN>
+                    <PubMedID>25965389</PubMedID>
+                    <PatentID>US20050159403A1</PatentID>
+                </Information>
+            </InformationList>
+        "#;
+        let mut list =
+            InformationList::from_api_response_with(response(xml), ParseMode::Lenient).unwrap();
+        let information = list.informations.pop().unwrap();
+        assert_eq!(information.cids, vec![2244]);
+        assert_eq!(information.rns, vec!["50-78-2", "11126-35-5"]);
+        assert_eq!(information.pubmed_ids, vec![25965389]);
+        assert_eq!(information.patent_ids, vec!["US20050159403A1"]);
+    }
+
+    #[test]
+    fn hierarchies() {
+        let xml = r#"<?xml version="1.0"?>
+            <Hierarchies xmlns="http://pubchem.ncbi.nlm.nih.gov/pug_rest">
+                <Hierarchy>
+                    <SourceName>MeSH</SourceName>
+                    <SourceID>MeSH Tree</SourceID>
+                    <RootID>root</RootID>
+                    <HID>1</HID>
+                    <Information>
+                        <Name>MeSH Tree</Name>
+                        <URL>https://www.nlm.nih.gov/mesh/meshhome.html</URL>
+                        <HNID>1949451</HNID>
+                        <ChildID>node_1</ChildID>
+                        <HasCountsOfType>CID</HasCountsOfType>
+                    </Information>
+                    <Node>
+                        <NodeID>node_1</NodeID>
+                        <ParentID>root</ParentID>
+                        <ChildID>node_2</ChildID>
+                        <Information>
+                            <Name>Chemicals and Drugs Category</Name>
+                            <HNID>1949452</HNID>
+                            <Counts>
+                                <Type>CID</Type>
+                                <Count>123456</Count>
+                            </Counts>
+                        </Information>
+                    </Node>
+                    <Node>
+                        <NodeID>node_2</NodeID>
+                        <ParentID>node_1</ParentID>
+                        <Information>
+                            <Name>Organic Chemicals</Name>
+                            <Description>A broad class of substances.</Description>
+                            <HNID>1949453</HNID>
+                        </Information>
+                    </Node>
+                </Hierarchy>
+            </Hierarchies>
+        "#;
+        let hierarchies =
+            Hierarchies::from_api_response_with(response(xml), ParseMode::Lenient).unwrap();
+        assert_eq!(hierarchies.len(), 1);
+
+        let mesh = &hierarchies[0];
+        assert_eq!(mesh.source_name, "MeSH");
+        assert_eq!(mesh.hid, Some(1));
+        assert_eq!(mesh.information.as_ref().unwrap().hnid, Some(1949451));
+
+        let roots = mesh.roots().collect::<Vec<_>>();
+        assert_eq!(roots.len(), 1);
+        assert_eq!(roots[0].information.name, "Chemicals and Drugs Category");
+        assert_eq!(roots[0].information.counts[0].count, 123456);
+
+        let children = mesh.children(roots[0]).collect::<Vec<_>>();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].information.hnid, Some(1949453));
+        assert_eq!(mesh.parents(children[0]).next(), Some(roots[0]));
+    }
+
+    #[test]
+    fn property_table() {
+        let xml = r#"<?xml version="1.0"?>
+            <PropertyTable xmlns="http://pubchem.ncbi.nlm.nih.gov/pug_rest">
+                <Properties>
+                    <CID>2244</CID>
+                    <MolecularWeight>180.16</MolecularWeight>
+                    <ExactMass>180.04225873</ExactMass>
+                    <HeavyAtomCount>13</HeavyAtomCount>
+                    <Fingerprint2D>AAADcYBAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA==</Fingerprint2D>
+                    <Title>Aspirin</Title>
+                </Properties>
+            </PropertyTable>
+        "#;
+        let table =
+            PropertyTable::from_api_response_with(response(xml), ParseMode::Lenient).unwrap();
+        let properties = &table[0];
+
+        let exact_mass = properties.exact_mass.as_ref().unwrap();
+        assert_eq!(exact_mass.as_str(), "180.04225873");
+        assert_eq!(exact_mass.value(), 180.04225873);
+        assert_eq!(
+            properties.molecular_weight,
+            Some("180.160".parse().unwrap())
+        );
+
+        assert_eq!(
+            properties.get(&CompoundProperty::HeavyAtomCount),
+            Ok(Some(PropertyValue::Integer(13)))
+        );
+        assert_eq!(
+            properties.get(&CompoundProperty::Fingerprint2D),
+            Ok(Some(PropertyValue::Fingerprint(
+                PubChemFingerprint::from_bits(vec![0, 9])
+            )))
+        );
+        assert_eq!(properties.get(&CompoundProperty::XLogP), Ok(None));
+
+        let invalid = Properties {
+            fingerprint_2d: Some(String::from("not base64")),
+            ..Default::default()
+        };
+        assert!(invalid.get(&CompoundProperty::Fingerprint2D).is_err());
+        assert!(invalid.values().any(|r| r.is_err()));
+
+        let names = properties
+            .values()
+            .map(|r| r.unwrap().0.name())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "MolecularWeight",
+                "Title",
+                "ExactMass",
+                "HeavyAtomCount",
+                "Fingerprint2D"
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_elements() {
+        let xml = r#"<?xml version="1.0"?>
+            <PropertyTable xmlns="http://pubchem.ncbi.nlm.nih.gov/pug_rest">
+                <Properties>
+                    <CID>2244</CID>
+                    <NewProperty><Value>1</Value></NewProperty>
+                    <Title>Aspirin</Title>
+                </Properties>
+            </PropertyTable>
+        "#;
+        let table =
+            PropertyTable::from_api_response_with(response(xml), ParseMode::Lenient).unwrap();
+        assert_eq!(table[0].cid, 2244);
+        assert_eq!(table[0].title.as_deref(), Some("Aspirin"));
+        assert_eq!(table[0].extra["NewProperty"], vec![String::from("1")]);
+
+        let result = PropertyTable::from_api_response_with(response(xml), ParseMode::Strict);
+        match result {
+            Err(Error::UnexpectedElement { element, parent }) => {
+                assert_eq!(element, "NewProperty");
+                assert_eq!(parent, "Properties");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn information_dates() {
+        let xml = r#"<?xml version="1.0"?>
+            <InformationList xmlns="http://pubchem.ncbi.nlm.nih.gov/pug_rest">
+                <Information>
+                    <CID>2244</CID>
+                    <CreationDate>
+                        <Year>2005</Year>
+                        <Month>3</Month>
+                        <Day>25</Day>
+                    </CreationDate>
+                </Information>
+            </InformationList>
+        "#;
+        let list =
+            InformationList::from_api_response_with(response(xml), ParseMode::Lenient).unwrap();
+        let date = list.informations[0].creation_date.unwrap();
+        assert_eq!(date, DateTime::date(2005, 3, 25).unwrap());
+        assert_eq!(date.to_string(), "2005-03-25");
+
+        // invalid dates are kept as reported
+        let invalid = xml.replace("<Day>25</Day>", "<Day>32</Day>");
+        let list = InformationList::from_api_response_with(response(&invalid), ParseMode::Lenient)
+            .unwrap();
+        let date = list.informations[0].creation_date.unwrap();
+        assert_eq!(date.day, Some(32));
+        assert!(date.validate().is_err());
+    }
+
+    #[test]
+    fn datetime() {
+        let date = DateTime::date(2020, 2, 29).unwrap();
+        let datetime = date.with_time(14, 2, 5).unwrap();
+        assert_eq!(datetime.to_string(), "2020-02-29T14:02:05");
+        assert_eq!("2020-02-29T14:02:05".parse::<DateTime>().unwrap(), datetime);
+        assert_eq!("2020-02-29".parse::<DateTime>().unwrap(), date);
+        assert!(date < datetime);
+
+        let year = "2005".parse::<DateTime>().unwrap();
+        assert_eq!(year.to_string(), "2005");
+        assert_eq!(year.month, None);
+        assert!(year < date);
+
+        assert!(DateTime::date(2021, 2, 29).is_err());
+        assert!(date.with_time(24, 0, 0).is_err());
+        assert!("2005-13".parse::<DateTime>().is_err());
+        assert!("2005T10:00".parse::<DateTime>().is_err());
+        assert!("2005-01-01-01".parse::<DateTime>().is_err());
+        let no_year = DateTime {
+            month: Some(1),
+            ..Default::default()
+        };
+        assert!(no_year.validate().is_err());
+    }
+
+    #[test]
+    fn datetime_roundtrip() {
+        let dates = [
+            [2004, 2, 29, 0, 7, 59],
+            [1999, 12, 1, 23, 0, 0],
+            [5, 1, 31, 9, 59, 1],
+        ];
+        for components in dates.iter() {
+            for precision in 1..=components.len() {
+                let mut c = components[..precision].iter().copied();
+                let dt = DateTime {
+                    year: c.next(),
+                    month: c.next(),
+                    day: c.next(),
+                    hour: c.next(),
+                    minute: c.next(),
+                    second: c.next(),
+                };
+                assert!(dt.validate().is_ok(), "invalid date: {:?}", dt);
+                let s = dt.to_string();
+                assert_eq!(s.parse::<DateTime>().unwrap(), dt, "{}", s);
+            }
+        }
+        let hour = "2005-03-26T14".parse::<DateTime>().unwrap();
+        assert_eq!(hour.minute, None);
+        assert_eq!(hour.to_string(), "2005-03-26T14");
+
+        let negative = DateTime::date(-5, 3, 1).unwrap();
+        assert_eq!(negative.to_string(), "-0005-03-01");
+        assert_eq!("-0005-03-01".parse::<DateTime>().unwrap(), negative);
+        assert_eq!(DateTime::default().to_string(), "");
+        assert_eq!("".parse::<DateTime>().unwrap(), DateTime::default());
+        assert!("--5".parse::<DateTime>().is_err());
+        assert!("-".parse::<DateTime>().is_err());
+    }
+
+    #[test]
+    fn datetime_roundtrip_random() {
+        // a linear congruential generator, to get reproducible dates
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = |n: i64| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            (state >> 33) as i64 % n
+        };
+        for _ in 0..10_000 {
+            let years = [i32::MIN, -10_000, 0, 9_999, 10_000, i32::MAX];
+            let year = match next(4) {
+                0 => years[next(years.len() as i64) as usize],
+                _ => next(20_001) as i32 - 10_000,
+            };
+            let components = [
+                year,
+                next(12) as i32 + 1,
+                next(28) as i32 + 1,
+                next(24) as i32,
+                next(60) as i32,
+                next(60) as i32,
+            ];
+            let mut c = components[..next(7) as usize].iter().copied();
+            let dt = DateTime {
+                year: c.next(),
+                month: c.next(),
+                day: c.next(),
+                hour: c.next(),
+                minute: c.next(),
+                second: c.next(),
+            };
+            let s = dt.to_string();
+            assert_eq!(s.parse::<DateTime>().unwrap(), dt, "{:?}", s);
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn datetime_chrono() {
+        let datetime = DateTime::date(2005, 3, 25)
+            .and_then(|dt| dt.with_time(8, 30, 0))
+            .unwrap();
+        let naive = chrono::NaiveDateTime::try_from(datetime).unwrap();
+        assert_eq!(naive.to_string(), "2005-03-25 08:30:00");
+        assert_eq!(DateTime::from(naive), datetime);
+        let year = DateTime {
+            year: Some(2005),
+            ..Default::default()
+        };
+        assert!(chrono::NaiveDate::try_from(year).is_err());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn datetime_time() {
+        let datetime = DateTime::date(2005, 3, 25)
+            .and_then(|dt| dt.with_time(8, 30, 0))
+            .unwrap();
+        let primitive = time::PrimitiveDateTime::try_from(datetime).unwrap();
+        assert_eq!(primitive.hour(), 8);
+        assert_eq!(DateTime::from(primitive), datetime);
+        let year = DateTime {
+            year: Some(2005),
+            ..Default::default()
+        };
+        assert!(time::Date::try_from(year).is_err());
+    }
+
+    #[test]
+    fn waiting() {
+        let xml = r#"<?xml version="1.0"?>
+            <Waiting xmlns="http://pubchem.ncbi.nlm.nih.gov/pug_rest">
+                <ListKey>2816386564474430218</ListKey>
+                <Message>Your request is running</Message>
+            </Waiting>
+        "#;
+        let waiting = Waiting::from_api_response_with(response(xml), ParseMode::Lenient).unwrap();
+        assert_eq!(waiting.list_key, "2816386564474430218");
+        assert_eq!(waiting.message.unwrap(), "Your request is running");
+    }
+}
@@ -0,0 +1,664 @@
+//! Raw data types returned by the PUG-View API.
+
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::io::BufRead;
+
+use quick_xml::events::BytesStart;
+use quick_xml::Reader;
+
+use crate::error::Error;
+use crate::parser::FromXml;
+use crate::parser::ParseMode;
+
+/// A PubChem record with the sections of its summary page.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct Record {
+    pub record_type: String,
+    pub record_number: i32,
+    pub record_title: Option<String>,
+    pub sections: Vec<Section>,
+    pub references: Vec<Reference>,
+}
+
+impl Record {
+    /// Find the first section with the given heading, at any depth.
+    pub fn section(&self, heading: &str) -> Option<&Section> {
+        self.sections.iter().find_map(|s| s.section(heading))
+    }
+
+    /// Get the reference with the given reference number, if any.
+    pub fn reference(&self, number: i32) -> Option<&Reference> {
+        self.references
+            .iter()
+            .find(|r| r.reference_number == Some(number))
+    }
+}
+
+impl FromXml for Record {
+    #[allow(unused_variables)]
+    fn from_xml<B: BufRead>(
+        event: &BytesStart,
+        reader: &mut Reader<B>,
+        buffer: &mut Vec<u8>,
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        debug_assert_eq!(event.local_name(), b"Record");
+
+        let mut record = Record::default();
+        parse_inner! {event, reader, buffer, mode,
+            e @ b"RecordType" => {
+                record.record_type = reader.read_text(e.name(), buffer)?;
+            },
+            e @ b"RecordNumber" => {
+                record.record_number = reader.read_text(e.name(), buffer)?.parse()?;
+            },
+            e @ b"RecordTitle" => {
+                record.record_title = Some(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"Section" => {
+                record.sections.push(Section::from_xml(&e, reader, buffer, mode)?);
+            },
+            e @ b"Reference" => {
+                record.references.push(Reference::from_xml(&e, reader, buffer, mode)?);
+            },
+        };
+
+        Ok(record)
+    }
+}
+
+/// A section of a record, with nested subsections and information.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct Section {
+    pub toc_heading: String,
+    pub toc_id: Option<i32>,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub sections: Vec<Section>,
+    pub information: Vec<Information>,
+}
+
+impl Section {
+    /// Find the first section with the given heading, including this one.
+    pub fn section(&self, heading: &str) -> Option<&Section> {
+        if self.toc_heading == heading {
+            Some(self)
+        } else {
+            self.sections.iter().find_map(|s| s.section(heading))
+        }
+    }
+
+    /// Iterate over the information of this section and all its subsections.
+    pub fn all_information(&self) -> Box<dyn Iterator<Item = &Information> + '_> {
+        Box::new(
+            self.information
+                .iter()
+                .chain(self.sections.iter().flat_map(Section::all_information)),
+        )
+    }
+}
+
+impl FromXml for Section {
+    #[allow(unused_variables)]
+    fn from_xml<B: BufRead>(
+        event: &BytesStart,
+        reader: &mut Reader<B>,
+        buffer: &mut Vec<u8>,
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        debug_assert_eq!(event.local_name(), b"Section");
+
+        let mut section = Section::default();
+        parse_inner! {event, reader, buffer, mode,
+            e @ b"TOCHeading" => {
+                section.toc_heading = reader.read_text(e.name(), buffer)?;
+            },
+            e @ b"TOCID" => {
+                section.toc_id = Some(reader.read_text(e.name(), buffer)?.parse()?);
+            },
+            e @ b"Description" => {
+                section.description = Some(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"URL" => {
+                section.url = Some(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"DisplayControls" => {
+                reader.read_to_end(e.name(), &mut Vec::new())?;
+            },
+            e @ b"Section" => {
+                section.sections.push(Section::from_xml(&e, reader, buffer, mode)?);
+            },
+            e @ b"Information" => {
+                section.information.push(Information::from_xml(&e, reader, buffer, mode)?);
+            },
+        };
+
+        Ok(section)
+    }
+}
+
+/// A single piece of information, with its value and provenance.
+///
+/// This type is also used for the `Data` elements of annotations, which
+/// additionally have a TOC heading.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct Information {
+    pub reference_number: Option<i32>,
+    pub toc_heading: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub references: Vec<String>,
+    pub license_note: Option<String>,
+    pub license_url: Option<String>,
+    pub url: Option<String>,
+    pub value: Value,
+}
+
+impl FromXml for Information {
+    #[allow(unused_variables)]
+    fn from_xml<B: BufRead>(
+        event: &BytesStart,
+        reader: &mut Reader<B>,
+        buffer: &mut Vec<u8>,
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        debug_assert!(matches!(event.local_name(), b"Information" | b"Data"));
+
+        macro_rules! set_option {
+            ($reader:ident, $buffer:ident, $e:ident, $i:ident . $field:ident) => {{
+                $i.$field = Some($reader.read_text($e.name(), $buffer)?);
+            }};
+        }
+
+        let mut i = Information::default();
+        parse_inner! {event, reader, buffer, mode,
+            e @ b"ReferenceNumber" => {
+                i.reference_number = Some(reader.read_text(e.name(), buffer)?.parse()?);
+            },
+            e @ b"TOCHeading" => set_option!(reader, buffer, e, i.toc_heading),
+            e @ b"Name" => set_option!(reader, buffer, e, i.name),
+            e @ b"Description" => set_option!(reader, buffer, e, i.description),
+            e @ b"Reference" => {
+                i.references.push(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"LicenseNote" => set_option!(reader, buffer, e, i.license_note),
+            e @ b"LicenseURL" => set_option!(reader, buffer, e, i.license_url),
+            e @ b"URL" => set_option!(reader, buffer, e, i.url),
+            e @ b"Value" => {
+                i.value = Value::from_xml(&e, reader, buffer, mode)?;
+            },
+        };
+
+        Ok(i)
+    }
+}
+
+/// The value of an information, either textual, numeric or external.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct Value {
+    pub string_with_markup: Vec<StringWithMarkup>,
+    pub number: Vec<f64>,
+    pub unit: Option<String>,
+    pub date_iso8601: Vec<String>,
+    pub boolean: Vec<bool>,
+    pub binary: Vec<String>,
+    pub external_data_url: Vec<String>,
+    pub external_table_name: Option<String>,
+    pub external_table_num_rows: Option<i32>,
+    pub mime_type: Option<String>,
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if !self.string_with_markup.is_empty() {
+            for (i, s) in self.string_with_markup.iter().enumerate() {
+                if i > 0 {
+                    f.write_str("\n")?;
+                }
+                f.write_str(&s.string)?;
+            }
+        } else if !self.number.is_empty() {
+            for (i, n) in self.number.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{}", n)?;
+            }
+            if let Some(unit) = &self.unit {
+                write!(f, " {}", unit)?;
+            }
+        } else if !self.date_iso8601.is_empty() {
+            f.write_str(&self.date_iso8601.join(", "))?;
+        } else if !self.boolean.is_empty() {
+            for (i, b) in self.boolean.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{}", b)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromXml for Value {
+    #[allow(unused_variables)]
+    fn from_xml<B: BufRead>(
+        event: &BytesStart,
+        reader: &mut Reader<B>,
+        buffer: &mut Vec<u8>,
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        debug_assert_eq!(event.local_name(), b"Value");
+
+        let mut v = Value::default();
+        parse_inner! {event, reader, buffer, mode,
+            e @ b"StringWithMarkup" => {
+                v.string_with_markup.push(StringWithMarkup::from_xml(&e, reader, buffer, mode)?);
+            },
+            e @ b"Number" => {
+                v.number.push(reader.read_text(e.name(), buffer)?.parse()?);
+            },
+            e @ b"Unit" => {
+                v.unit = Some(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"DateISO8601" => {
+                v.date_iso8601.push(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"Boolean" => {
+                v.boolean.push(reader.read_text(e.name(), buffer)? == "true");
+            },
+            e @ b"Binary" => {
+                v.binary.push(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"ExternalDataURL" => {
+                v.external_data_url.push(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"ExternalTableName" => {
+                v.external_table_name = Some(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"ExternalTableNumRows" => {
+                v.external_table_num_rows = Some(reader.read_text(e.name(), buffer)?.parse()?);
+            },
+            e @ b"MimeType" => {
+                v.mime_type = Some(reader.read_text(e.name(), buffer)?);
+            },
+        };
+
+        Ok(v)
+    }
+}
+
+/// A string with markup annotating some of its spans.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct StringWithMarkup {
+    pub string: String,
+    pub markup: Vec<Markup>,
+}
+
+impl Display for StringWithMarkup {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(&self.string)
+    }
+}
+
+impl FromXml for StringWithMarkup {
+    #[allow(unused_variables)]
+    fn from_xml<B: BufRead>(
+        event: &BytesStart,
+        reader: &mut Reader<B>,
+        buffer: &mut Vec<u8>,
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        debug_assert_eq!(event.local_name(), b"StringWithMarkup");
+
+        let mut s = StringWithMarkup::default();
+        parse_inner! {event, reader, buffer, mode,
+            e @ b"String" => {
+                s.string = reader.read_text(e.name(), buffer)?;
+            },
+            e @ b"Markup" => {
+                s.markup.push(Markup::from_xml(&e, reader, buffer, mode)?);
+            },
+        };
+
+        Ok(s)
+    }
+}
+
+/// The markup of a span of a [`StringWithMarkup`].
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct Markup {
+    pub start: i32,
+    pub length: i32,
+    pub url: Option<String>,
+    pub source: Option<String>,
+    pub ty: Option<String>,
+    pub extra: Option<String>,
+}
+
+impl FromXml for Markup {
+    #[allow(unused_variables)]
+    fn from_xml<B: BufRead>(
+        event: &BytesStart,
+        reader: &mut Reader<B>,
+        buffer: &mut Vec<u8>,
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        debug_assert_eq!(event.local_name(), b"Markup");
+
+        let mut m = Markup::default();
+        parse_inner! {event, reader, buffer, mode,
+            e @ b"Start" => {
+                m.start = reader.read_text(e.name(), buffer)?.parse()?;
+            },
+            e @ b"Length" => {
+                m.length = reader.read_text(e.name(), buffer)?.parse()?;
+            },
+            e @ b"URL" => {
+                m.url = Some(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"Source" => {
+                m.source = Some(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"Type" => {
+                m.ty = Some(reader.read_text(e.name(), buffer)?);
+            },
+            e @ b"Extra" => {
+                m.extra = Some(reader.read_text(e.name(), buffer)?);
+            },
+        };
+
+        Ok(m)
+    }
+}
+
+/// A reference to the source of some information in a record.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct Reference {
+    pub reference_number: Option<i32>,
+    pub source_name: Option<String>,
+    pub source_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub license_note: Option<String>,
+    pub license_url: Option<String>,
+    pub is_toxnet: Option<bool>,
+    pub anid: Option<i32>,
+}
+
+impl FromXml for Reference {
+    #[allow(unused_variables)]
+    fn from_xml<B: BufRead>(
+        event: &BytesStart,
+        reader: &mut Reader<B>,
+        buffer: &mut Vec<u8>,
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        debug_assert_eq!(event.local_name(), b"Reference");
+
+        macro_rules! set_option {
+            ($reader:ident, $buffer:ident, $e:ident, $r:ident . $field:ident) => {{
+                $r.$field = Some($reader.read_text($e.name(), $buffer)?);
+            }};
+            ($reader:ident, $buffer:ident, $e:ident, $r:ident . $field:ident ?) => {{
+                $r.$field = Some($reader.read_text($e.name(), $buffer)?.parse()?);
+            }};
+        }
+
+        let mut r = Reference::default();
+        parse_inner! {event, reader, buffer, mode,
+            e @ b"ReferenceNumber" => set_option!(reader, buffer, e, r.reference_number ?),
+            e @ b"SourceName" => set_option!(reader, buffer, e, r.source_name),
+            e @ b"SourceID" => set_option!(reader, buffer, e, r.source_id),
+            e @ b"Name" => set_option!(reader, buffer, e, r.name),
+            e @ b"Description" => set_option!(reader, buffer, e, r.description),
+            e @ b"URL" => set_option!(reader, buffer, e, r.url),
+            e @ b"LicenseNote" => set_option!(reader, buffer, e, r.license_note),
+            e @ b"LicenseURL" => set_option!(reader, buffer, e, r.license_url),
+            e @ b"IsToxnet" => {
+                r.is_toxnet = Some(reader.read_text(e.name(), buffer)? == "true");
+            },
+            e @ b"ANID" => set_option!(reader, buffer, e, r.anid ?),
+        };
+
+        Ok(r)
+    }
+}
+
+/// A page of annotations matching a heading.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct Annotations {
+    pub annotations: Vec<Annotation>,
+    pub page: i32,
+    pub total_pages: i32,
+}
+
+impl FromXml for Annotations {
+    #[allow(unused_variables)]
+    fn from_xml<B: BufRead>(
+        event: &BytesStart,
+        reader: &mut Reader<B>,
+        buffer: &mut Vec<u8>,
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        debug_assert_eq!(event.local_name(), b"Annotations");
+
+        let mut a = Annotations::default();
+        parse_inner! {event, reader, buffer, mode,
+            e @ b"Annotation" => {
+                a.annotations.push(Annotation::from_xml(&e, reader, buffer, mode)?);
+            },
+            e @ b"Page" => {
+                a.page = reader.read_text(e.name(), buffer)?.parse()?;
+            },
+            e @ b"TotalPages" => {
+                a.total_pages = reader.read_text(e.name(), buffer)?.parse()?;
+            },
+        };
+
+        Ok(a)
+    }
+}
+
+/// A single annotation, with its data and the records it is linked to.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct Annotation {
+    pub source_name: Option<String>,
+    pub source_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub license_note: Option<String>,
+    pub license_url: Option<String>,
+    pub anid: Option<i32>,
+    pub data: Vec<Information>,
+    pub linked_records: LinkedRecords,
+}
+
+impl FromXml for Annotation {
+    #[allow(unused_variables)]
+    fn from_xml<B: BufRead>(
+        event: &BytesStart,
+        reader: &mut Reader<B>,
+        buffer: &mut Vec<u8>,
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        debug_assert_eq!(event.local_name(), b"Annotation");
+
+        macro_rules! set_option {
+            ($reader:ident, $buffer:ident, $e:ident, $a:ident . $field:ident) => {{
+                $a.$field = Some($reader.read_text($e.name(), $buffer)?);
+            }};
+        }
+
+        let mut a = Annotation::default();
+        parse_inner! {event, reader, buffer, mode,
+            e @ b"SourceName" => set_option!(reader, buffer, e, a.source_name),
+            e @ b"SourceID" => set_option!(reader, buffer, e, a.source_id),
+            e @ b"Name" => set_option!(reader, buffer, e, a.name),
+            e @ b"Description" => set_option!(reader, buffer, e, a.description),
+            e @ b"URL" => set_option!(reader, buffer, e, a.url),
+            e @ b"LicenseNote" => set_option!(reader, buffer, e, a.license_note),
+            e @ b"LicenseURL" => set_option!(reader, buffer, e, a.license_url),
+            e @ b"ANID" => {
+                a.anid = Some(reader.read_text(e.name(), buffer)?.parse()?);
+            },
+            e @ b"Data" => {
+                a.data.push(Information::from_xml(&e, reader, buffer, mode)?);
+            },
+            e @ b"LinkedRecords" => {
+                a.linked_records = LinkedRecords::from_xml(&e, reader, buffer, mode)?;
+            },
+        };
+
+        Ok(a)
+    }
+}
+
+/// The records an annotation is linked to.
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct LinkedRecords {
+    pub cids: Vec<i32>,
+    pub sids: Vec<i32>,
+}
+
+impl FromXml for LinkedRecords {
+    #[allow(unused_variables)]
+    fn from_xml<B: BufRead>(
+        event: &BytesStart,
+        reader: &mut Reader<B>,
+        buffer: &mut Vec<u8>,
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        debug_assert_eq!(event.local_name(), b"LinkedRecords");
+
+        let mut l = LinkedRecords::default();
+        parse_inner! {event, reader, buffer, mode,
+            b"CID" => {
+                l.cids.push(reader.read_text(b"CID", buffer)?.parse()?);
+            },
+            b"SID" => {
+                l.sids.push(reader.read_text(b"SID", buffer)?.parse()?);
+            },
+        };
+
+        Ok(l)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::parser::FromApiResponse;
+
+    fn response(body: &str) -> ureq::Response {
+        ureq::Response::new(200, "OK", body).unwrap()
+    }
+
+    #[test]
+    fn record() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <Record xmlns="http://pubchem.ncbi.nlm.nih.gov/pug_view">
+                <RecordType>CID</RecordType>
+                <RecordNumber>2244</RecordNumber>
+                <RecordTitle>Aspirin</RecordTitle>
+                <Section>
+                    <TOCHeading>Chemical and Physical Properties</TOCHeading>
+                    <Description>Various chemical and physical properties</Description>
+                    <Section>
+                        <TOCHeading>Experimental Properties</TOCHeading>
+                        <Section>
+                            <TOCHeading>Melting Point</TOCHeading>
+                            <DisplayControls>
+                                <ListType>Columns</ListType>
+                            </DisplayControls>
+                            <Information>
+                                <ReferenceNumber>12</ReferenceNumber>
+                                <Value>
+                                    <StringWithMarkup>
+                                        <String>135 °C</String>
+                                    </StringWithMarkup>
+                                </Value>
+                            </Information>
+                            <Information>
+                                <ReferenceNumber>13</ReferenceNumber>
+                                <Value>
+                                    <Number>135</Number>
+                                    <Unit>°C</Unit>
+                                </Value>
+                            </Information>
+                        </Section>
+                    </Section>
+                </Section>
+                <Reference>
+                    <ReferenceNumber>12</ReferenceNumber>
+                    <SourceName>DrugBank</SourceName>
+                    <SourceID>DB00945</SourceID>
+                    <Name>Acetylsalicylic acid</Name>
+                    <URL>https://www.drugbank.ca/drugs/DB00945</URL>
+                </Reference>
+            </Record>
+        "#;
+        let record = Record::from_api_response_with(response(xml), ParseMode::Lenient).unwrap();
+        assert_eq!(record.record_type, "CID");
+        assert_eq!(record.record_number, 2244);
+        assert_eq!(record.record_title.as_deref(), Some("Aspirin"));
+
+        let section = record.section("Melting Point").unwrap();
+        let values = section
+            .all_information()
+            .map(|i| i.value.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec!["135 °C", "135 °C"]);
+
+        let reference = record.reference(12).unwrap();
+        assert_eq!(reference.source_name.as_deref(), Some("DrugBank"));
+    }
+
+    #[test]
+    fn annotations() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <Annotations xmlns="http://pubchem.ncbi.nlm.nih.gov/pug_view">
+                <Annotation>
+                    <SourceName>Hazardous Substances Data Bank (HSDB)</SourceName>
+                    <SourceID>1</SourceID>
+                    <Name>Formaldehyde</Name>
+                    <Data>
+                        <TOCHeading>Boiling Point</TOCHeading>
+                        <Reference>Lide, D.R. CRC Handbook of Chemistry and Physics</Reference>
+                        <Value>
+                            <StringWithMarkup>
+                                <String>-19.5 °C</String>
+                            </StringWithMarkup>
+                        </Value>
+                    </Data>
+                    <ANID>1</ANID>
+                    <LinkedRecords>
+                        <CID>712</CID>
+                    </LinkedRecords>
+                </Annotation>
+                <Page>1</Page>
+                <TotalPages>12</TotalPages>
+            </Annotations>
+        "#;
+        let annotations =
+            Annotations::from_api_response_with(response(xml), ParseMode::Lenient).unwrap();
+        assert_eq!(annotations.page, 1);
+        assert_eq!(annotations.total_pages, 12);
+        assert_eq!(annotations.annotations.len(), 1);
+
+        let annotation = &annotations.annotations[0];
+        assert_eq!(annotation.anid, Some(1));
+        assert_eq!(annotation.linked_records.cids, vec![712]);
+        assert_eq!(
+            annotation.data[0].toc_heading.as_deref(),
+            Some("Boiling Point")
+        );
+        assert_eq!(annotation.data[0].value.to_string(), "-19.5 °C");
+    }
+}
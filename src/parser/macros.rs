@@ -1,6 +1,6 @@
 #[allow(unused_macros)]
 macro_rules! parse_inner {
-    ($event:expr, $reader:expr, $buffer:expr, $($rest:tt)*) => ({
+    ($event:expr, $reader:expr, $buffer:expr, $mode:expr, $($rest:tt)*) => ({
         #[allow(unused_imports)]
         loop {
             use $crate::quick_xml::events::BytesEnd;
@@ -12,12 +12,9 @@ macro_rules! parse_inner {
             match $reader.read_event($buffer) {
                 Ok(Event::Start(ref x)) => {
                     parse_inner_impl!(x, x.local_name(), $($rest)*);
-                    $reader.read_to_end(x.local_name(), &mut Vec::new())?;
-                    unimplemented!(
-                        "`{}` in `{}`",
-                        String::from_utf8_lossy(x.local_name()),
-                        String::from_utf8_lossy($event.local_name())
-                    );
+                    // skip unknown elements, e.g. from an addition to the schema
+                    #[allow(unreachable_code)]
+                    $crate::parser::read_unknown(x, &$event, $reader, $mode)?;
                 }
                 Err(e) => {
                     return Err(Error::from(e));
@@ -46,6 +43,13 @@ macro_rules! parse_inner {
 macro_rules! parse_inner_impl {
     ( $x:ident, $name:expr ) => ();
     ( $x:ident, $name:expr, ) => ();
+    ( $x:ident, $name:expr, $e:ident @ _ => $r:expr $(,)? ) => (
+        {
+            let $e = $x.clone().into_owned();
+            $r;
+            continue;
+        }
+    );
     ( $x:ident, $name:expr, $e:ident @ $l:expr => $r:expr ) => (
         if $name == $l {
             let $e = $x.clone().into_owned();
@@ -71,19 +75,28 @@ macro_rules! parse_inner_impl {
 
 #[allow(unused_macros)]
 macro_rules! parse_comment {
-    ( $event:ident, $reader:ident, $buffer:ident, $comment:ident ) => {
-        parse_comment!{$event, $reader, $buffer, $comment, }
+    ( $event:ident, $reader:ident, $buffer:ident, $mode:ident, $comment:ident ) => {
+        parse_comment!{$event, $reader, $buffer, $mode, $comment, }
     };
-    ( $event:ident, $reader:ident, $buffer:ident, $comment:ident, $($rest:tt)* ) => {
-        parse_inner!{$event, $reader, $buffer,
+    ( $event:ident, $reader:ident, $buffer:ident, $mode:ident, $comment:ident, $($rest:tt)* ) => {
+        parse_inner!{$event, $reader, $buffer, $mode,
             b"text" => {
                 $comment.text.push($reader.read_text(b"text", $buffer)?);
             },
             m @ b"molecule" => {
-                $comment.molecule = Molecule::from_xml(&m, $reader, $buffer)
+                $comment.molecule = Molecule::from_xml(&m, $reader, $buffer, $mode)
                     .map(Some)?;
             },
             $($rest)*
         }
     }
 }
+
+#[allow(unused_macros)]
+macro_rules! push_extra {
+    ( $event:ident, $reader:ident, $mode:ident, $e:ident, $p:ident . $field:ident ) => {{
+        let text = $crate::parser::read_unknown(&$e, $event, $reader, $mode)?;
+        let name = String::from_utf8_lossy($e.local_name()).to_string();
+        $p.$field.entry(name).or_default().push(text);
+    }};
+}
@@ -1,7 +1,14 @@
 #[macro_use]
 mod macros;
 
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
 use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Cursor;
+use std::io::Read;
+use std::marker::PhantomData;
 
 use quick_xml::events::BytesStart;
 use quick_xml::events::Event;
@@ -10,39 +17,226 @@ use quick_xml::Reader;
 
 use crate::error::Error;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// The handling of XML elements unknown to the parsers.
+///
+/// The mode is set per client, see for instance [`Compound::parse_mode`],
+/// and applies to all the responses parsed for that client.
+///
+/// [`Compound::parse_mode`]: crate::Compound::parse_mode
+pub enum ParseMode {
+    /// Skip unknown elements, or store their text in the `extra` field of
+    /// the types that have one.
+    #[default]
+    Lenient,
+    /// Report unknown elements as [`Error::UnexpectedElement`] errors.
+    Strict,
+}
+
+/// Read an element unknown to the parser of its `parent` element.
+///
+/// Returns the text content of the element in lenient mode, or an error
+/// in strict mode.
+pub fn read_unknown<B: BufRead>(
+    element: &BytesStart,
+    parent: &BytesStart,
+    reader: &mut Reader<B>,
+    mode: ParseMode,
+) -> Result<String, Error> {
+    if mode == ParseMode::Strict {
+        return Err(Error::UnexpectedElement {
+            element: String::from_utf8_lossy(element.local_name()).to_string(),
+            parent: String::from_utf8_lossy(parent.local_name()).to_string(),
+        });
+    }
+
+    let mut buffer = Vec::new();
+    let mut text = String::new();
+    let mut depth = 0usize;
+    loop {
+        buffer.clear();
+        match reader.read_event(&mut buffer)? {
+            Event::Start(_) => depth += 1,
+            Event::End(_) if depth == 0 => return Ok(text),
+            Event::End(_) => depth -= 1,
+            Event::Text(e) | Event::CData(e) => {
+                let t = e.unescape_and_decode(reader)?;
+                if !t.trim().is_empty() {
+                    if !text.is_empty() {
+                        text.push(' ');
+                    }
+                    text.push_str(t.trim());
+                }
+            }
+            Event::Eof => {
+                let e = String::from_utf8_lossy(element.local_name()).to_string();
+                return Err(Error::from(XmlError::UnexpectedEof(e)));
+            }
+            _ => (),
+        }
+    }
+}
+
 /// A trait for types that can be parsed from an XML element.
 pub trait FromXml: Sized {
     fn from_xml<B: BufRead>(
         event: &BytesStart,
         reader: &mut Reader<B>,
         buffer: &mut Vec<u8>,
+        mode: ParseMode,
     ) -> Result<Self, Error>;
 }
 
 /// A trait for types that can be returned by the REST API.
 pub trait FromApiResponse: FromXml {
-    fn from_api_response(response: ureq::Response) -> Result<Self, Error>;
+    /// Parse a response with the given parsing mode.
+    fn from_api_response_with(response: ureq::Response, mode: ParseMode) -> Result<Self, Error>;
+}
+
+/// The reader used to parse API responses.
+pub type ResponseReader = Reader<BufReader<Box<dyn Read + Send + Sync + 'static>>>;
+
+/// Create a reader for the given response, and read up to its root element.
+fn open_response(
+    response: ureq::Response,
+    buffer: &mut Vec<u8>,
+) -> Result<(ResponseReader, BytesStart<'static>), Error> {
+    open_reader(response.into_reader(), buffer)
+}
+
+/// Create a reader for the given XML document, and read up to its root element.
+fn open_reader(
+    reader: Box<dyn Read + Send + Sync + 'static>,
+    buffer: &mut Vec<u8>,
+) -> Result<(ResponseReader, BytesStart<'static>), Error> {
+    let mut xml = Reader::from_reader(BufReader::new(reader));
+
+    let event = loop {
+        buffer.clear();
+        match xml.read_event(buffer) {
+            Err(e) => return Err(Error::from(e)),
+            Ok(Event::Start(e)) => break e.into_owned(),
+            Ok(Event::Eof) => {
+                let e = String::from("xml");
+                return Err(Error::from(XmlError::UnexpectedEof(e)));
+            }
+            _ => (),
+        }
+    };
+
+    Ok((xml, event))
 }
 
 impl<T: FromXml> FromApiResponse for T {
-    fn from_api_response(response: ureq::Response) -> Result<Self, Error> {
-        let reader = response.into_reader();
-        let mut xml = Reader::from_reader(std::io::BufReader::new(reader));
+    fn from_api_response_with(response: ureq::Response, mode: ParseMode) -> Result<Self, Error> {
         let mut buffer = Vec::new();
+        let (mut xml, event) = open_response(response, &mut buffer)?;
+        T::from_xml(&event, &mut xml, &mut buffer, mode)
+    }
+}
+
+/// Parse an XML document already in memory, if its root element is `root`.
+///
+/// Returns `None` if the document is not well-formed before its root
+/// element, or if its root element has another name.
+pub fn from_document<T: FromXml>(
+    document: Vec<u8>,
+    root: &[u8],
+    mode: ParseMode,
+) -> Result<Option<T>, Error> {
+    let mut buffer = Vec::new();
+    let (mut xml, event) = match open_reader(Box::new(Cursor::new(document)), &mut buffer) {
+        Ok((xml, event)) if event.local_name() == root => (xml, event),
+        _ => return Ok(None),
+    };
+    T::from_xml(&event, &mut xml, &mut buffer, mode).map(Some)
+}
+
+/// An iterator over the children of the root element of an API response.
+///
+/// Children are parsed one at a time as the response is read, so that
+/// large responses can be processed without being loaded in memory.
+pub struct ElementStream<T> {
+    reader: ResponseReader,
+    buffer: Vec<u8>,
+    root: Vec<u8>,
+    child: &'static [u8],
+    mode: ParseMode,
+    done: bool,
+    marker: PhantomData<T>,
+}
+
+impl<T: FromXml> ElementStream<T> {
+    /// Open a stream over the `child` elements of the `root` element of a response.
+    pub fn new(
+        response: ureq::Response,
+        root: &[u8],
+        child: &'static [u8],
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        let mut buffer = Vec::new();
+        let (reader, event) = open_response(response, &mut buffer)?;
+        if event.local_name() != root {
+            return Err(Error::UnexpectedElement {
+                element: String::from_utf8_lossy(event.local_name()).to_string(),
+                parent: String::from("xml"),
+            });
+        }
+        Ok(Self {
+            reader,
+            buffer,
+            root: event.local_name().to_vec(),
+            child,
+            mode,
+            done: false,
+            marker: PhantomData,
+        })
+    }
+}
 
-        let event = loop {
-            buffer.clear();
-            match xml.read_event(&mut buffer) {
-                Err(e) => return Err(Error::from(e)),
-                Ok(Event::Start(e)) => break e.to_owned(),
+impl<T> Debug for ElementStream<T> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("ElementStream")
+            .field("root", &String::from_utf8_lossy(&self.root))
+            .field("child", &String::from_utf8_lossy(self.child))
+            .field("mode", &self.mode)
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+impl<T: FromXml> Iterator for ElementStream<T> {
+    type Item = Result<T, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.buffer.clear();
+            let result = match self.reader.read_event(&mut self.buffer) {
+                Ok(Event::Start(e)) if e.local_name() == self.child => {
+                    let e = e.into_owned();
+                    T::from_xml(&e, &mut self.reader, &mut self.buffer, self.mode)
+                }
+                Ok(Event::Start(e)) => {
+                    let e = e.into_owned();
+                    match self.reader.read_to_end(e.local_name(), &mut self.buffer) {
+                        Ok(()) => continue,
+                        Err(err) => Err(Error::from(err)),
+                    }
+                }
+                Ok(Event::End(ref e)) if e.local_name() == self.root.as_slice() => {
+                    self.done = true;
+                    return None;
+                }
                 Ok(Event::Eof) => {
-                    let e = String::from("xml");
-                    return Err(Error::from(XmlError::UnexpectedEof(e)));
+                    let e = String::from_utf8_lossy(&self.root).to_string();
+                    Err(Error::from(XmlError::UnexpectedEof(e)))
                 }
-                _ => (),
-            }
-        };
-
-        T::from_xml(&event, &mut xml, &mut buffer)
+                Err(e) => Err(Error::from(e)),
+                _ => continue,
+            };
+            // stop after an error, since the reader may be in an invalid state
+            self.done = result.is_err();
+            return Some(result);
+        }
+        None
     }
 }
@@ -0,0 +1,274 @@
+//! Reports of the resolution of input identifiers to Compound IDs.
+//!
+//! A [`ResolutionReport`] is returned by [`Compounds::resolve`], and records
+//! for every input identifier the Compound IDs it matched, so that the
+//! outcome of large queries can be inspected or exported without losing
+//! track of which input produced which compound.
+//!
+//! [`Compounds::resolve`]: crate::Compounds::resolve
+
+use std::io::Result as IoResult;
+use std::io::Write;
+use std::ops::Deref;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The outcome of the resolution of a single input identifier.
+pub enum ResolutionStatus {
+    /// The identifier matched exactly one compound.
+    Found,
+    /// The identifier matched several compounds.
+    Ambiguous,
+    /// The identifier did not match any compound.
+    NotFound,
+    /// The request for the identifier failed, with the given message.
+    Error(String),
+}
+
+impl ResolutionStatus {
+    /// Get the name of the status, as used in the exported reports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResolutionStatus::Found => "found",
+            ResolutionStatus::Ambiguous => "ambiguous",
+            ResolutionStatus::NotFound => "not found",
+            ResolutionStatus::Error(_) => "error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The resolution of a single input identifier.
+pub struct Resolution {
+    /// The input identifier, as given to the query.
+    pub input: String,
+    /// The Compound IDs matching the identifier.
+    pub cids: Vec<i32>,
+    /// The outcome of the resolution.
+    pub status: ResolutionStatus,
+}
+
+impl Resolution {
+    /// Create a new resolution for an identifier matching the given CIDs.
+    pub fn new(input: String, cids: Vec<i32>) -> Self {
+        let status = match cids.len() {
+            0 => ResolutionStatus::NotFound,
+            1 => ResolutionStatus::Found,
+            _ => ResolutionStatus::Ambiguous,
+        };
+        Self {
+            input,
+            cids,
+            status,
+        }
+    }
+
+    /// Create a new resolution for an identifier whose request failed.
+    pub fn error(input: String, message: String) -> Self {
+        Self {
+            input,
+            cids: Vec::new(),
+            status: ResolutionStatus::Error(message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// A report of the resolution of several input identifiers, in input order.
+pub struct ResolutionReport {
+    /// The resolution of every input identifier, in input order.
+    pub resolutions: Vec<Resolution>,
+}
+
+impl ResolutionReport {
+    /// Iterate over the resolutions with the same status as `status`.
+    ///
+    /// Error messages are ignored, so any [`ResolutionStatus::Error`] can be
+    /// used to iterate over all the failed resolutions.
+    pub fn with_status(&self, status: &ResolutionStatus) -> impl Iterator<Item = &Resolution> + '_ {
+        let name = status.name();
+        self.resolutions
+            .iter()
+            .filter(move |r| r.status.name() == name)
+    }
+
+    /// Write the report in CSV format.
+    ///
+    /// The report has an `input`, `status`, `cids` and `error` columns,
+    /// with the Compound IDs of each input separated by semicolons.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        fn escape(field: &str) -> String {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        }
+
+        writeln!(writer, "input,status,cids,error")?;
+        for r in &self.resolutions {
+            let cids = r.cids.iter().map(|cid| cid.to_string()).collect::<Vec<_>>();
+            let error = match &r.status {
+                ResolutionStatus::Error(message) => message.as_str(),
+                _ => "",
+            };
+            writeln!(
+                writer,
+                "{},{},{},{}",
+                escape(&r.input),
+                r.status.name(),
+                cids.join(";"),
+                escape(error)
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write the report in JSON format.
+    ///
+    /// The report is written as an array of objects with an `input`,
+    /// `status`, `cids` and `error` keys.
+    pub fn write_json<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        fn escape(s: &str) -> String {
+            let mut escaped = String::with_capacity(s.len() + 2);
+            escaped.push('"');
+            for c in s.chars() {
+                match c {
+                    '"' => escaped.push_str("\\\""),
+                    '\\' => escaped.push_str("\\\\"),
+                    '\n' => escaped.push_str("\\n"),
+                    '\r' => escaped.push_str("\\r"),
+                    '\t' => escaped.push_str("\\t"),
+                    c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+                    c => escaped.push(c),
+                }
+            }
+            escaped.push('"');
+            escaped
+        }
+
+        write!(writer, "[")?;
+        for (i, r) in self.resolutions.iter().enumerate() {
+            if i > 0 {
+                write!(writer, ",")?;
+            }
+            let cids = r.cids.iter().map(|cid| cid.to_string()).collect::<Vec<_>>();
+            let error = match &r.status {
+                ResolutionStatus::Error(message) => escape(message),
+                _ => String::from("null"),
+            };
+            write!(
+                writer,
+                "{{\"input\":{},\"status\":{},\"cids\":[{}],\"error\":{}}}",
+                escape(&r.input),
+                escape(r.status.name()),
+                cids.join(","),
+                error
+            )?;
+        }
+        write!(writer, "]")
+    }
+
+    /// Get the report in CSV format.
+    pub fn to_csv(&self) -> String {
+        let mut buffer = Vec::new();
+        self.write_csv(&mut buffer)
+            .expect("writing to a `Vec` never fails");
+        String::from_utf8(buffer).expect("report is always valid UTF-8")
+    }
+
+    /// Get the report in JSON format.
+    pub fn to_json(&self) -> String {
+        let mut buffer = Vec::new();
+        self.write_json(&mut buffer)
+            .expect("writing to a `Vec` never fails");
+        String::from_utf8(buffer).expect("report is always valid UTF-8")
+    }
+}
+
+impl Deref for ResolutionReport {
+    type Target = Vec<Resolution>;
+    fn deref(&self) -> &Self::Target {
+        &self.resolutions
+    }
+}
+
+impl IntoIterator for ResolutionReport {
+    type Item = Resolution;
+    type IntoIter = <Vec<Resolution> as IntoIterator>::IntoIter;
+    fn into_iter(self) -> Self::IntoIter {
+        self.resolutions.into_iter()
+    }
+}
+
+impl FromIterator<Resolution> for ResolutionReport {
+    fn from_iter<I: IntoIterator<Item = Resolution>>(iter: I) -> Self {
+        Self {
+            resolutions: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<Resolution> for ResolutionReport {
+    fn extend<I: IntoIterator<Item = Resolution>>(&mut self, iter: I) {
+        self.resolutions.extend(iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn report() -> ResolutionReport {
+        vec![
+            Resolution::new(String::from("aspirin"), vec![2244]),
+            Resolution::new(String::from("glucose, anhydrous"), vec![5793, 79025]),
+            Resolution::new(String::from("not \"a\" compound"), vec![]),
+            Resolution::error(String::from("water"), String::from("server busy: retry")),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn status() {
+        let report = report();
+        assert_eq!(report[0].status, ResolutionStatus::Found);
+        assert_eq!(report[1].status, ResolutionStatus::Ambiguous);
+        assert_eq!(report[2].status, ResolutionStatus::NotFound);
+        let errors = report
+            .with_status(&ResolutionStatus::Error(String::new()))
+            .collect::<Vec<_>>();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].input, "water");
+    }
+
+    #[test]
+    fn csv() {
+        assert_eq!(
+            report().to_csv(),
+            concat!(
+                "input,status,cids,error\n",
+                "aspirin,found,2244,\n",
+                "\"glucose, anhydrous\",ambiguous,5793;79025,\n",
+                "\"not \"\"a\"\" compound\",not found,,\n",
+                "water,error,,server busy: retry\n",
+            )
+        );
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            report().to_json(),
+            concat!(
+                "[",
+                r#"{"input":"aspirin","status":"found","cids":[2244],"error":null},"#,
+                r#"{"input":"glucose, anhydrous","status":"ambiguous","cids":[5793,79025],"error":null},"#,
+                r#"{"input":"not \"a\" compound","status":"not found","cids":[],"error":null},"#,
+                r#"{"input":"water","status":"error","cids":[],"error":"server busy: retry"}"#,
+                "]",
+            )
+        );
+    }
+}
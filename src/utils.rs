@@ -1,6 +1,14 @@
+use std::borrow::Cow;
 use std::fmt::Display;
 use std::fmt::Write;
 use std::iter::Iterator;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+
+use crate::error::ParseError;
 
 macro_rules! named_enum {
     ($(#[$attributes:meta])*
@@ -19,6 +27,19 @@ macro_rules! named_enum {
                     $($name::$variant => stringify!($variant)),*
                 }
             }
+
+            /// Get all the variants of the enum, in declaration order.
+            pub fn variants() -> &'static [Self] {
+                &[$($name::$variant),*]
+            }
+
+            /// Get the enum variant with the given name, if any.
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $(stringify!($variant) => Some($name::$variant),)*
+                    _ => None,
+                }
+            }
         }
     };
 }
@@ -43,3 +64,327 @@ where
         joined
     }
 }
+
+/// Check that an SDF record starts with a well-formed MOL block.
+///
+/// Only the structure of the connection table is checked (header block,
+/// counts line, atom and bond blocks, and `M  END` terminator), the
+/// chemistry itself is left for PubChem to validate.
+pub fn validate_sdf(sdf: &str) -> Result<(), ParseError> {
+    let error = |msg: String| Err(ParseError::Sdf(msg));
+    let lines = sdf.lines().collect::<Vec<&str>>();
+    if lines.len() < 4 {
+        return error(String::from("missing header block or counts line"));
+    }
+
+    let counts = lines[3];
+    if counts.contains("V3000") {
+        let ctab = lines[4..].iter().map(|l| l.trim_end());
+        let mut begin = false;
+        for line in ctab {
+            match line {
+                "M  V30 BEGIN CTAB" => begin = true,
+                "M  V30 END CTAB" if begin => return Ok(()),
+                "M  END" => break,
+                _ => (),
+            }
+        }
+        return error(String::from("missing V3000 connection table"));
+    }
+
+    let parse_count = |range: std::ops::Range<usize>, what: &str| {
+        counts
+            .get(range)
+            .and_then(|s| s.trim().parse::<usize>().ok())
+            .ok_or_else(|| ParseError::Sdf(format!("invalid {} count in counts line", what)))
+    };
+    let n_atoms = parse_count(0..3, "atom")?;
+    let n_bonds = parse_count(3..6, "bond")?;
+    if lines.len() < 4 + n_atoms + n_bonds {
+        return error(format!(
+            "expected {} atoms and {} bonds, found only {} lines",
+            n_atoms,
+            n_bonds,
+            lines.len() - 4
+        ));
+    }
+
+    for (i, line) in lines[4..4 + n_atoms].iter().enumerate() {
+        let coordinates = [0..10, 10..20, 20..30].into_iter().all(|r| {
+            line.get(r)
+                .and_then(|s| s.trim().parse::<f64>().ok())
+                .is_some()
+        });
+        let symbol = line.get(31..34).map(str::trim).unwrap_or_default();
+        if !coordinates || symbol.is_empty() {
+            return error(format!("invalid atom line {}", i + 1));
+        }
+    }
+
+    for (i, line) in lines[4 + n_atoms..4 + n_atoms + n_bonds].iter().enumerate() {
+        let valid = [0..3, 3..6].into_iter().all(|r| {
+            line.get(r)
+                .and_then(|s| s.trim().parse::<usize>().ok())
+                .map(|atom| atom >= 1 && atom <= n_atoms)
+                .unwrap_or(false)
+        });
+        if !valid {
+            return error(format!("invalid bond line {}", i + 1));
+        }
+    }
+
+    let end = lines[4 + n_atoms + n_bonds..]
+        .iter()
+        .take_while(|l| !l.starts_with("$$$$"))
+        .any(|l| l.trim_end() == "M  END");
+    if end {
+        Ok(())
+    } else {
+        error(String::from("missing `M  END` terminator"))
+    }
+}
+
+/// Decode a standard base64 string, ignoring whitespace.
+///
+/// Returns `None` if the string contains invalid characters or is not
+/// correctly padded.
+pub fn decode_base64(s: &str) -> Option<Vec<u8>> {
+    fn value(c: u8) -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some((c - b'A') as u32),
+            b'a'..=b'z' => Some((c - b'a' + 26) as u32),
+            b'0'..=b'9' => Some((c - b'0' + 52) as u32),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    }
+
+    let chars = s
+        .bytes()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect::<Vec<u8>>();
+    if chars.len() % 4 != 0 {
+        return None;
+    }
+
+    let mut decoded = Vec::with_capacity(chars.len() / 4 * 3);
+    for (i, chunk) in chars.chunks(4).enumerate() {
+        let last = i == chars.len() / 4 - 1;
+        let padding = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+        if padding > 2 || (padding > 0 && !last) {
+            return None;
+        }
+        let mut n = 0;
+        for &c in &chunk[..4 - padding] {
+            n = (n << 6) | value(c)?;
+        }
+        n <<= 6 * padding as u32;
+        let bytes = [(n >> 16) as u8, (n >> 8) as u8, n as u8];
+        decoded.extend_from_slice(&bytes[..3 - padding]);
+    }
+    Some(decoded)
+}
+
+/// Encode a single form field as a `multipart/form-data` body.
+///
+/// Returns the boundary used to delimit the field alongside the body.
+pub fn multipart(name: &str, value: &str) -> (String, String) {
+    let mut boundary = String::from("----pubchem-rs-boundary");
+    while value.contains(&boundary) {
+        boundary.push('-');
+    }
+    let body = format!(
+        "--{b}\r\nContent-Disposition: form-data; name=\"{n}\"\r\n\r\n{v}\r\n--{b}--\r\n",
+        b = boundary,
+        n = name,
+        v = value,
+    );
+    (boundary, body)
+}
+
+/// Shorten a string to at most `max` characters for display purposes.
+///
+/// Only the first line of the string is kept, and the number of characters
+/// left out is reported when the string is truncated.
+pub fn truncate(s: &str, max: usize) -> Cow<'_, str> {
+    let line = s.lines().next().unwrap_or_default();
+    let end = line.char_indices().nth(max).map_or(line.len(), |(i, _)| i);
+    if end == s.len() {
+        Cow::Borrowed(s)
+    } else {
+        let omitted = s[end..].chars().count();
+        Cow::Owned(format!("{}... ({} more characters)", &s[..end], omitted))
+    }
+}
+
+/// Parse the value of a `Retry-After` header into a delay from `now`.
+///
+/// The header may contain either a number of seconds, or an HTTP date in
+/// the IMF-fixdate format (e.g. `Sun, 06 Nov 1994 08:49:37 GMT`). Dates
+/// in the past give a zero delay.
+pub fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let fields = value.split_whitespace().collect::<Vec<_>>();
+    let (day, month, year, time) = match fields.as_slice() {
+        [_, day, month, year, time, "GMT"] => (day, month, year, time),
+        _ => return None,
+    };
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let month = MONTHS.iter().position(|m| m == month)? as i64 + 1;
+    let day = day.parse::<i64>().ok()?;
+    let year = year.parse::<i64>().ok()?;
+    let mut hms = time.split(':').map(|x| x.parse::<i64>().ok());
+    let (h, m, s) = (hms.next()??, hms.next()??, hms.next()??);
+
+    // days since the Unix epoch, from Howard Hinnant's `days_from_civil`
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    let timestamp = u64::try_from(days * 86400 + h * 3600 + m * 60 + s).ok()?;
+    let date = SystemTime::UNIX_EPOCH + Duration::from_secs(timestamp);
+    Some(date.duration_since(now).unwrap_or_default())
+}
+
+/// A limiter spacing out events shared between several threads.
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    /// Create a new limiter allowing at most `per_second` events per second.
+    pub fn new(per_second: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / per_second.max(1),
+            next: Mutex::new(None),
+        }
+    }
+
+    /// Block the current thread until the next event is allowed.
+    pub fn wait(&self) {
+        let now = Instant::now();
+        let start = {
+            let mut next = self.next.lock().unwrap_or_else(|e| e.into_inner());
+            let start = next.map_or(now, |next| next.max(now));
+            *next = Some(start + self.interval);
+            start
+        };
+        if start > now {
+            thread::sleep(start - now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const ACETONE: &str = "acetone
+  manual
+
+  4  3  0  0  0  0  0  0  0  0999 V2000
+    0.0000    0.0000    0.0000 C   0  0  0  0  0  0  0  0  0  0  0  0
+    1.2990    0.7500    0.0000 C   0  0  0  0  0  0  0  0  0  0  0  0
+   -1.2990    0.7500    0.0000 C   0  0  0  0  0  0  0  0  0  0  0  0
+    0.0000   -1.5000    0.0000 O   0  0  0  0  0  0  0  0  0  0  0  0
+  1  2  1  0  0  0  0
+  1  3  1  0  0  0  0
+  1  4  2  0  0  0  0
+M  END
+$$$$
+";
+
+    #[test]
+    fn rate_limiter() {
+        let limiter = RateLimiter::new(50);
+        let start = Instant::now();
+        for _ in 0..5 {
+            limiter.wait();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(80));
+    }
+
+    #[test]
+    fn truncate() {
+        assert_eq!(super::truncate("2244,180", 64), "2244,180");
+        assert_eq!(
+            super::truncate("2244,180,5793", 8),
+            "2244,180... (5 more characters)"
+        );
+        assert_eq!(
+            super::truncate("acetone\n  -OEChem-\n", 64),
+            "acetone... (12 more characters)"
+        );
+    }
+
+    #[test]
+    fn parse_retry_after() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(784111777);
+        assert_eq!(
+            super::parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            super::parse_retry_after("Sun, 06 Nov 1994 08:50:07 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            super::parse_retry_after("Sun, 06 Nov 1994 08:00:00 GMT", now),
+            Some(Duration::from_secs(0))
+        );
+        assert_eq!(super::parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn decode_base64() {
+        assert_eq!(super::decode_base64("").unwrap(), b"");
+        assert_eq!(super::decode_base64("cHViY2hlbQ==").unwrap(), b"pubchem");
+        assert_eq!(
+            super::decode_base64("cHViY2hlbS5y\ncw==").unwrap(),
+            b"pubchem.rs"
+        );
+        assert_eq!(super::decode_base64("AAADcQ").as_deref(), None);
+        assert_eq!(super::decode_base64("AA=A").as_deref(), None);
+        assert_eq!(super::decode_base64("AA*A").as_deref(), None);
+    }
+
+    #[test]
+    fn validate_sdf_v2000() {
+        assert_eq!(validate_sdf(ACETONE), Ok(()));
+    }
+
+    #[test]
+    fn validate_sdf_truncated() {
+        let truncated = ACETONE.lines().take(9).collect::<Vec<_>>().join("\n");
+        assert!(validate_sdf(&truncated).is_err());
+        assert!(validate_sdf("CC(=O)C").is_err());
+    }
+
+    #[test]
+    fn validate_sdf_bad_bond() {
+        let sdf = ACETONE.replace("  1  4  2  0", "  1  5  2  0");
+        assert_eq!(
+            validate_sdf(&sdf),
+            Err(ParseError::Sdf(String::from("invalid bond line 3")))
+        );
+    }
+
+    #[test]
+    fn validate_sdf_missing_end() {
+        let sdf = ACETONE.replace("M  END\n", "");
+        assert!(validate_sdf(&sdf).is_err());
+    }
+}